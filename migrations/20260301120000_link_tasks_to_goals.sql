@@ -0,0 +1,4 @@
+-- Tasks can contribute to a goal; used for progress and deadline forecasting
+ALTER TABLE tasks ADD COLUMN goal_id UUID REFERENCES goals(id) ON DELETE SET NULL;
+
+CREATE INDEX tasks_goal_id_idx ON tasks(goal_id);
@@ -0,0 +1,2 @@
+-- IANA timezone name used to decide what "today" means for a user
+ALTER TABLE users ADD COLUMN timezone VARCHAR(64) NOT NULL DEFAULT 'UTC';
@@ -0,0 +1,7 @@
+-- Flexible habit schedules: specific weekdays, N times per week/month, every N days
+ALTER TABLE habits ALTER COLUMN frequency TYPE VARCHAR(20); -- + weekdays, times_per_week, times_per_month, every_n_days
+
+ALTER TABLE habits
+    ADD COLUMN weekdays SMALLINT[],  -- ISO weekday numbers (1 = Monday) for 'weekdays'
+    ADD COLUMN target_count INT,     -- completions per period for 'times_per_week' / 'times_per_month'
+    ADD COLUMN interval_days INT;    -- period length for 'every_n_days'
@@ -0,0 +1,7 @@
+-- Measured habits ("read 30 pages"): a day only counts once its logged value reaches daily_target
+ALTER TABLE habits
+    ADD COLUMN unit VARCHAR(32),
+    ADD COLUMN daily_target DOUBLE PRECISION;
+
+-- Sum of the partial entries logged for that day
+ALTER TABLE habit_completions ADD COLUMN value DOUBLE PRECISION;
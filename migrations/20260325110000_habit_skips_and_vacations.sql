@@ -0,0 +1,26 @@
+-- Days a habit was deliberately not done; they neither keep nor break a streak
+CREATE TABLE habit_skips (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    habit_id UUID NOT NULL REFERENCES habits(id) ON DELETE CASCADE,
+    skipped_on DATE NOT NULL,
+    kind VARCHAR(10) NOT NULL DEFAULT 'skip', -- skip, freeze
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(habit_id, skipped_on)
+);
+
+-- Vacations pause every habit of the user for the (inclusive) date range
+CREATE TABLE vacations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ends_on >= starts_on)
+);
+
+CREATE INDEX vacations_user_id_idx ON vacations(user_id);
+
+-- Streak freezes left to spend; more are earned by keeping streaks going
+ALTER TABLE users ADD COLUMN streak_freezes INT NOT NULL DEFAULT 2;
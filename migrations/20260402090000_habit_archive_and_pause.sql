@@ -0,0 +1,4 @@
+-- Archived habits drop out of listings but keep their history; paused ones aren't due until paused_until has passed
+ALTER TABLE habits
+    ADD COLUMN archived_at TIMESTAMPTZ,
+    ADD COLUMN paused_until DATE;
@@ -0,0 +1,13 @@
+-- Server-side timer state so pauses and early stops are tracked
+ALTER TABLE pomodoro_sessions
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'running', -- running, paused, completed, interrupted
+    ADD COLUMN paused_at TIMESTAMPTZ,                         -- set while paused
+    ADD COLUMN paused_seconds INT NOT NULL DEFAULT 0;         -- total time spent paused
+
+-- Sessions that already ended ran their full length or were cut short
+UPDATE pomodoro_sessions
+SET status = CASE
+    WHEN ended_at >= started_at + make_interval(mins => duration_minutes) THEN 'completed'
+    ELSE 'interrupted'
+END
+WHERE ended_at IS NOT NULL;
@@ -0,0 +1,22 @@
+-- At most one open pomodoro session per user
+
+-- Keep each user's most recent open session and interrupt the rest
+UPDATE pomodoro_sessions ps
+SET ended_at = NOW(),
+    paused_seconds = ps.paused_seconds
+        + COALESCE(EXTRACT(EPOCH FROM NOW() - ps.paused_at)::int, 0),
+    paused_at = NULL,
+    status = 'interrupted'
+WHERE ps.ended_at IS NULL
+  AND EXISTS (
+      SELECT 1 FROM pomodoro_sessions newer
+      WHERE newer.user_id = ps.user_id
+        AND newer.ended_at IS NULL
+        AND (newer.started_at, newer.id) > (ps.started_at, ps.id)
+  );
+
+CREATE UNIQUE INDEX idx_pomodoro_sessions_one_active
+    ON pomodoro_sessions(user_id) WHERE ended_at IS NULL;
+
+-- 'short_break' doesn't fit in the original VARCHAR(10)
+ALTER TABLE pomodoro_sessions ALTER COLUMN session_type TYPE VARCHAR(20);
@@ -0,0 +1,10 @@
+-- Per-user pomodoro cycle settings
+CREATE TABLE pomodoro_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    focus_minutes INT NOT NULL DEFAULT 25,
+    short_break_minutes INT NOT NULL DEFAULT 5,
+    long_break_minutes INT NOT NULL DEFAULT 15,
+    long_break_every INT NOT NULL DEFAULT 4, -- focus sessions per long break
+    auto_start_next BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
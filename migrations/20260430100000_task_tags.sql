@@ -0,0 +1,5 @@
+-- Free-form labels on tasks, used to group focus time
+ALTER TABLE tasks ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
+
+-- Reports filter focus sessions by the user's local start date
+CREATE INDEX idx_pomodoro_sessions_user_started ON pomodoro_sessions(user_id, started_at);
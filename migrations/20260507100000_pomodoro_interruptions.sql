@@ -0,0 +1,11 @@
+-- Why a pomodoro session got interrupted
+CREATE TABLE pomodoro_interruptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES pomodoro_sessions(id) ON DELETE CASCADE,
+    kind VARCHAR(10) NOT NULL, -- internal, external
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pomodoro_interruptions_session ON pomodoro_interruptions(session_id);
@@ -0,0 +1,4 @@
+-- Sessions closed by the server because their client never ended them
+ALTER TABLE pomodoro_sessions ADD COLUMN auto_closed BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_pomodoro_sessions_open ON pomodoro_sessions(started_at) WHERE ended_at IS NULL;
@@ -0,0 +1,17 @@
+-- Effort estimates on tasks; estimated_minutes wins over estimated_pomodoros when both are set
+ALTER TABLE tasks
+    ADD COLUMN estimated_pomodoros INT,
+    ADD COLUMN estimated_minutes INT;
+
+-- Time spent on a task outside of pomodoro sessions
+CREATE TABLE task_time_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    minutes INT NOT NULL,
+    spent_on DATE NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_task_time_entries_task ON task_time_entries(task_id);
+CREATE INDEX idx_pomodoro_sessions_task ON pomodoro_sessions(task_id);
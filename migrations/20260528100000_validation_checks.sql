@@ -0,0 +1,35 @@
+-- Backstop for the request validation in the API: invariants only, the upper
+-- limits stay application policy. NOT VALID so rows written before validation
+-- existed don't block the migration; every new insert and update is checked.
+ALTER TABLE users
+    ADD CONSTRAINT users_username_not_blank CHECK (btrim(username) <> '') NOT VALID;
+
+ALTER TABLE goals
+    ADD CONSTRAINT goals_title_not_blank CHECK (btrim(title) <> '') NOT VALID;
+
+ALTER TABLE tasks
+    ADD CONSTRAINT tasks_title_not_blank CHECK (btrim(title) <> '') NOT VALID,
+    ADD CONSTRAINT tasks_estimated_pomodoros_positive CHECK (estimated_pomodoros > 0) NOT VALID,
+    ADD CONSTRAINT tasks_estimated_minutes_positive CHECK (estimated_minutes > 0) NOT VALID;
+
+ALTER TABLE task_time_entries
+    ADD CONSTRAINT task_time_entries_minutes_positive CHECK (minutes > 0) NOT VALID;
+
+ALTER TABLE habits
+    ADD CONSTRAINT habits_name_not_blank CHECK (btrim(name) <> '') NOT VALID,
+    ADD CONSTRAINT habits_weekdays_iso CHECK (weekdays <@ ARRAY[1, 2, 3, 4, 5, 6, 7]::SMALLINT[]) NOT VALID,
+    ADD CONSTRAINT habits_target_count_positive CHECK (target_count > 0) NOT VALID,
+    ADD CONSTRAINT habits_interval_days_positive CHECK (interval_days > 0) NOT VALID,
+    ADD CONSTRAINT habits_daily_target_positive CHECK (daily_target > 0) NOT VALID;
+
+ALTER TABLE habit_completions
+    ADD CONSTRAINT habit_completions_value_positive CHECK (value > 0) NOT VALID;
+
+ALTER TABLE pomodoro_sessions
+    ADD CONSTRAINT pomodoro_sessions_duration_positive CHECK (duration_minutes > 0) NOT VALID;
+
+ALTER TABLE pomodoro_settings
+    ADD CONSTRAINT pomodoro_settings_durations_positive CHECK (
+        focus_minutes > 0 AND short_break_minutes > 0 AND long_break_minutes > 0
+    ) NOT VALID,
+    ADD CONSTRAINT pomodoro_settings_long_break_every_positive CHECK (long_break_every > 0) NOT VALID;
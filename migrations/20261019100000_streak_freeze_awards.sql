@@ -0,0 +1,12 @@
+-- Only check-ins made on the day they're for count toward earning freezes.
+-- Existing rows can't tell, so they don't count.
+ALTER TABLE habit_completions ADD COLUMN same_day BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- A habit pays out at most one freeze per streak period, however often the
+-- check-in that reached it is undone and redone
+CREATE TABLE streak_freeze_awards (
+    habit_id UUID NOT NULL REFERENCES habits(id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    awarded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (habit_id, period_start)
+);
@@ -0,0 +1,16 @@
+-- Every stretch a habit was paused, so the paused days stay neutral for its
+-- streaks after it's resumed. habits.paused_until still marks the pause in
+-- progress.
+CREATE TABLE habit_pauses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    habit_id UUID NOT NULL REFERENCES habits(id) ON DELETE CASCADE,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    CHECK (ends_on >= starts_on)
+);
+
+CREATE INDEX habit_pauses_habit_id_idx ON habit_pauses(habit_id);
+
+-- When current pauses began wasn't recorded; count them from today
+INSERT INTO habit_pauses (habit_id, starts_on, ends_on)
+SELECT id, CURRENT_DATE, paused_until FROM habits WHERE paused_until >= CURRENT_DATE;
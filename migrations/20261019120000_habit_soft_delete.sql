@@ -0,0 +1,4 @@
+-- Deleting a habit hides it straight away; purge-deleted removes it and its
+-- history for good once it has been deleted for a while. Archiving is separate
+-- and keeps the history indefinitely.
+ALTER TABLE habits ADD COLUMN deleted_at TIMESTAMPTZ;
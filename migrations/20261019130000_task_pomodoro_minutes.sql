@@ -0,0 +1,11 @@
+-- The pomodoro length an estimate in pomodoros was made with, so that changing
+-- focus_minutes later doesn't rewrite the estimates of earlier tasks. Existing
+-- estimates take the length in use now.
+ALTER TABLE tasks ADD COLUMN pomodoro_minutes INT;
+
+UPDATE tasks t
+SET pomodoro_minutes = COALESCE(
+    (SELECT s.focus_minutes FROM pomodoro_settings s WHERE s.user_id = t.user_id),
+    25
+)
+WHERE t.estimated_pomodoros IS NOT NULL;
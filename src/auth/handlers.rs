@@ -1,140 +1,163 @@
-use argon2::password_hash::SaltString;
-use argon2::password_hash::rand_core::OsRng;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use axum::extract::Json;
 use axum::response::IntoResponse;
 use axum::{extract::State, http::StatusCode};
-use serde_json::json;
+use chrono_tz::Tz;
 
 use crate::auth::jwt::create_token;
-use crate::models::user::{CreateUser, LoginUser, User};
+use crate::auth::middleware::AuthUser;
+use crate::auth::password::{hash_password, verify_password};
+use crate::error::{AppError, Problem};
+use crate::metrics;
+use crate::models::user::{CreateUser, LoginUser, TokenResponse, UpdateUser, UserResponse};
+use crate::repo::{RepoError, users::NewUser};
 use crate::state::AppState;
+use crate::validation::ValidatedJson;
 
+#[utoipa::path(
+    post,
+    path = "/register",
+    tag = "auth",
+    security(()),
+    request_body = CreateUser,
+    responses(
+        (status = CREATED, body = UserResponse),
+        (status = CONFLICT, description = "`email_taken` or `username_taken`", body = Problem,
+         content_type = "application/problem+json"),
+    )
+)]
 pub async fn register(
     State(state): State<AppState>,
-    Json(body): Json<CreateUser>,
-) -> impl IntoResponse {
+    ValidatedJson(body): ValidatedJson<CreateUser>,
+) -> Result<impl IntoResponse, AppError> {
+    // Timezones are IANA names like "Europe/Tallinn"; reject anything chrono-tz doesn't know
+    let timezone = body.timezone.unwrap_or_else(|| "UTC".to_string());
+    if timezone.parse::<Tz>().is_err() {
+        return Err(AppError::field("timezone", "Unknown timezone"));
+    }
+
     // Check if a user with this email already exists
-    let existing = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
-        .bind(&body.email)
-        .fetch_optional(&state.db_pool)
-        .await;
-
-    match existing {
-        Err(_) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-                .into_response();
-        }
-        Ok(Some(_)) => {
-            return (
-                StatusCode::CONFLICT,
-                Json(json!({"error": "Email already in use"})),
-            )
-                .into_response();
-        }
-        Ok(None) => {} // good, continue
+    let existing = state.repos.users.find_by_email(&body.email).await?;
+
+    if existing.is_some() {
+        return Err(email_taken());
     }
 
-    // Generate a random salt and hash the password using Argon2
-    // Argon2 is a memory-hard hashing algorithm — much safer than bcrypt for passwords
-    let salt = SaltString::generate(&mut OsRng);
-    let password_hash = Argon2::default().hash_password(body.password.as_bytes(), &salt);
-
-    let password_hash = match password_hash {
-        Ok(hash) => hash.to_string(),
-        Err(_) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to hash password"})),
-            )
-                .into_response();
+    let password_hash = hash_password(&body.password)
+        .map_err(|err| AppError::Internal(format!("failed to hash password: {err}")))?;
+
+    let email = body.email.clone();
+    let user = match state
+        .repos
+        .users
+        .create(NewUser {
+            username: body.username,
+            email: body.email,
+            password_hash,
+            timezone,
+        })
+        .await
+    {
+        Ok(user) => user,
+        // Either the username is taken or the email was registered since the check above
+        Err(RepoError::Duplicate) => {
+            if state.repos.users.find_by_email(&email).await?.is_some() {
+                return Err(email_taken());
+            }
+            return Err(AppError::conflict(
+                "username_taken",
+                "Username already in use",
+            ));
         }
+        Err(err) => return Err(err.into()),
     };
 
-    // Insert the new user and return the created row using RETURNING *
-    let user = sqlx::query_as::<_, User>(
-        "INSERT INTO users (username, email, password_hash) VALUES ($1, $2, $3) RETURNING *",
-    )
-    .bind(&body.username)
-    .bind(&body.email)
-    .bind(&password_hash)
-    .fetch_one(&state.db_pool)
-    .await;
-
-    match user {
-        Ok(user) => (StatusCode::CREATED, Json(json!({"user": user}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to create user"})),
-        )
-            .into_response(),
-    }
+    Ok((StatusCode::CREATED, Json(UserResponse { user })))
+}
+
+fn email_taken() -> AppError {
+    AppError::conflict("email_taken", "Email already in use")
 }
 
+#[utoipa::path(
+    post,
+    path = "/login",
+    tag = "auth",
+    security(()),
+    request_body = LoginUser,
+    responses(
+        (status = OK, body = TokenResponse),
+        (status = UNAUTHORIZED, description = "Wrong email or password", body = Problem,
+         content_type = "application/problem+json"),
+    )
+)]
 pub async fn login(
     State(state): State<AppState>,
-    Json(body): Json<LoginUser>,
-) -> impl IntoResponse {
+    ValidatedJson(body): ValidatedJson<LoginUser>,
+) -> Result<impl IntoResponse, AppError> {
     // Try to find the user by email
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
-        .bind(&body.email)
-        .fetch_optional(&state.db_pool)
-        .await;
-
-    let user = match user {
-        Err(_) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-                .into_response();
-        }
-        // If no user found, return 401 — don't say "email not found" to avoid leaking info
-        Ok(None) => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Invalid credentials"})),
-            )
-                .into_response();
-        }
-        Ok(Some(u)) => u,
-    };
+    let user = state.repos.users.find_by_email(&body.email).await?;
 
-    // Parse the stored hash and verify the provided password against it
-    let parsed_hash = PasswordHash::new(&user.password_hash);
-    let parsed_hash = match parsed_hash {
-        Ok(h) => h,
-        Err(_) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to parse password hash"})),
-            )
-                .into_response();
-        }
+    // If no user found, return 401 — don't say "email not found" to avoid leaking info
+    let Some(user) = user else {
+        metrics::login(false);
+        return Err(AppError::Unauthorized("Invalid credentials"));
     };
 
-    let valid = Argon2::default()
-        .verify_password(body.password.as_bytes(), &parsed_hash)
-        .is_ok();
+    // Verify the provided password against the stored hash
+    let valid = verify_password(&body.password, &user.password_hash)
+        .map_err(|err| AppError::Internal(format!("failed to parse password hash: {err}")))?;
 
+    metrics::login(valid);
     if !valid {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid credentials"})),
-        )
-            .into_response();
+        return Err(AppError::Unauthorized("Invalid credentials"));
     }
 
     // Password is correct — create and return a JWT
-    match create_token(user.id, &state.jwt_secret) {
-        Ok(token) => (StatusCode::OK, Json(json!({"token": token}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to create token"})),
-        )
-            .into_response(),
+    let token = create_token(user.id, &state.jwt_secret, state.token_lifetime)
+        .map_err(|err| AppError::Internal(format!("failed to create token: {err}")))?;
+
+    Ok((StatusCode::OK, Json(TokenResponse { token })))
+}
+
+#[utoipa::path(get, path = "/me", tag = "auth", responses((status = OK, body = UserResponse)))]
+pub async fn get_me(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = state
+        .repos
+        .users
+        .find(auth.user_id)
+        .await?
+        .ok_or(AppError::NotFound("User"))?;
+
+    Ok((StatusCode::OK, Json(UserResponse { user })))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/me",
+    tag = "auth",
+    request_body = UpdateUser,
+    responses((status = OK, body = UserResponse))
+)]
+pub async fn update_me(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<UpdateUser>,
+) -> Result<impl IntoResponse, AppError> {
+    if let Some(timezone) = &body.timezone
+        && timezone.parse::<Tz>().is_err()
+    {
+        return Err(AppError::field("timezone", "Unknown timezone"));
     }
+
+    let user = state
+        .repos
+        .users
+        .update_timezone(auth.user_id, body.timezone)
+        .await?
+        .ok_or(AppError::NotFound("User"))?;
+
+    Ok((StatusCode::OK, Json(UserResponse { user })))
 }
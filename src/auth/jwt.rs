@@ -9,10 +9,14 @@ pub struct Claims {
     pub exp: usize,
 }
 
-pub fn create_token(user_id: Uuid, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
+pub fn create_token(
+    user_id: Uuid,
+    secret: &str,
+    lifetime: Duration,
+) -> Result<String, jsonwebtoken::errors::Error> {
     let claims = Claims {
         sub: user_id.to_string(),
-        exp: (chrono::Utc::now() + Duration::days(7)).timestamp() as usize,
+        exp: (chrono::Utc::now() + lifetime).timestamp() as usize,
     };
     encode(
         &Header::default(),
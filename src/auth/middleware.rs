@@ -1,8 +1,8 @@
-use axum::{Json, extract::FromRequestParts, http::StatusCode, http::request::Parts};
-use serde_json::json;
+use axum::{extract::FromRequestParts, http::request::Parts};
 use uuid::Uuid;
 
 use crate::auth::jwt::decode_token;
+use crate::error::AppError;
 use crate::state::AppState;
 
 pub struct AuthUser {
@@ -10,7 +10,7 @@ pub struct AuthUser {
 }
 
 impl FromRequestParts<AppState> for AuthUser {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = AppError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -26,29 +26,21 @@ impl FromRequestParts<AppState> for AuthUser {
         let token = match auth_header {
             Some(h) if h.starts_with("Bearer ") => &h[7..], // slice off "Bearer "
             _ => {
-                return Err((
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({"error": "Missing or invalid Authorization header"})),
+                return Err(AppError::Unauthorized(
+                    "Missing or invalid Authorization header",
                 ));
             }
         };
 
         // Decode and validate the JWT — like Django's TokenAuthentication
-        let claims = decode_token(token, &state.jwt_secret).map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Invalid or expired token"})),
-            )
-        })?;
+        let claims = decode_token(token, &state.jwt_secret)
+            .map_err(|_| AppError::Unauthorized("Invalid or expired token"))?;
 
         // Parse the UUID from the "sub" field in the token claims
-        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Invalid user ID in token"})),
-            )
-        })?;
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::Unauthorized("Invalid user ID in token"))?;
 
+        tracing::Span::current().record("user_id", tracing::field::display(user_id));
         Ok(AuthUser { user_id })
     }
 }
@@ -1,6 +1,7 @@
 pub mod handlers;
 pub mod jwt;
 pub mod middleware;
+pub mod password;
 pub mod router;
 
 pub use router::auth_router;
@@ -0,0 +1,20 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{Error, SaltString};
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+
+// Argon2 is a memory-hard hashing algorithm — much safer than bcrypt for passwords
+pub fn hash_password(password: &str) -> Result<String, Error> {
+    // Every hash gets its own random salt
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+// Err only when the stored hash itself can't be parsed
+pub fn verify_password(password: &str, stored_hash: &str) -> Result<bool, Error> {
+    let parsed_hash = PasswordHash::new(stored_hash)?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
@@ -1,10 +1,11 @@
-use axum::{Router, routing::post};
+use utoipa_axum::routes;
 
-use crate::auth::handlers::{login, register};
-use crate::state::AppState;
+use crate::auth::handlers;
+use crate::openapi::ApiRouter;
 
-pub fn auth_router() -> Router<AppState> {
-    Router::new()
-        .route("/register", post(register))
-        .route("/login", post(login))
+pub fn auth_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(handlers::register))
+        .routes(routes!(handlers::login))
+        .routes(routes!(handlers::get_me, handlers::update_me))
 }
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Productivity API server")]
+pub struct Cli {
+    /// TOML config file [default: $PROJEKT_CONFIG, else ./config.toml if present]
+    #[arg(long, value_name = "FILE", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Print the effective configuration (secrets redacted) and exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the HTTP server (the default)
+    Serve {
+        /// Apply pending migrations before accepting requests
+        #[arg(long)]
+        migrate: bool,
+    },
+    /// Manage the database schema
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+    /// Create a user; the password is prompted for, or read from piped stdin
+    CreateUser {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        email: String,
+        /// IANA timezone, e.g. Europe/Tallinn
+        #[arg(long, default_value = "UTC")]
+        timezone: String,
+    },
+    /// Set a new password for a user; the password is prompted for, or read from piped stdin
+    ResetPassword {
+        #[arg(long)]
+        email: String,
+    },
+    /// Permanently delete habits that were deleted a while ago
+    PurgeDeleted {
+        /// Only purge habits deleted at least this many days ago
+        #[arg(long, default_value_t = 90)]
+        older_than_days: u32,
+        /// List what would be purged without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Command::Serve { migrate: false }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateAction {
+    /// Apply all pending migrations
+    Up,
+    /// List migrations and whether each has been applied
+    Status,
+}
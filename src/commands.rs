@@ -0,0 +1,193 @@
+use std::io::{self, BufRead, IsTerminal};
+
+use chrono_tz::Tz;
+use sqlx::{
+    PgPool,
+    migrate::{Migrate, MigrateError},
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::password::hash_password,
+    db::{MIGRATOR, is_unique_violation},
+    models::user::{CreateUser, User},
+    validation::field_errors,
+};
+
+// Failures of the management subcommands; printed as-is by main
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+    #[error("could not connect to the database: {0}")]
+    Connect(sqlx::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("could not listen on {addr}: {source}")]
+    Listen { addr: String, source: io::Error },
+    #[error("migration failed: {0}")]
+    Migrate(#[from] MigrateError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+pub async fn migrate_up(pool: &PgPool) -> Result<(), CommandError> {
+    MIGRATOR.run(pool).await?;
+    println!("Database is up to date");
+    Ok(())
+}
+
+pub async fn migrate_status(pool: &PgPool) -> Result<(), CommandError> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    let applied = conn.list_applied_migrations().await?;
+
+    let mut pending = 0;
+    for migration in MIGRATOR
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+    {
+        let status = match applied.iter().find(|a| a.version == migration.version) {
+            Some(a) if a.checksum != migration.checksum => "applied, but the file has changed",
+            Some(_) => "applied",
+            None => {
+                pending += 1;
+                "pending"
+            }
+        };
+        println!(
+            "{:<16} {:<45} {status}",
+            migration.version, migration.description
+        );
+    }
+    println!("{pending} pending");
+    Ok(())
+}
+
+pub async fn create_user(
+    pool: &PgPool,
+    username: String,
+    email: String,
+    timezone: String,
+) -> Result<(), CommandError> {
+    let password = read_password()?;
+    let new_user = CreateUser {
+        username,
+        email,
+        password,
+        timezone: Some(timezone),
+    };
+    check(&new_user)?;
+    let timezone = new_user.timezone.unwrap_or_default();
+    if timezone.parse::<Tz>().is_err() {
+        return Err(CommandError::Invalid(format!(
+            "unknown timezone {timezone:?}"
+        )));
+    }
+
+    let password_hash = hash_password(&new_user.password)
+        .map_err(|err| CommandError::Invalid(format!("could not hash password: {err}")))?;
+    let user = sqlx::query_as::<_, User>(
+        "INSERT INTO users (username, email, password_hash, timezone) \
+         VALUES ($1, $2, $3, $4) RETURNING *",
+    )
+    .bind(&new_user.username)
+    .bind(&new_user.email)
+    .bind(&password_hash)
+    .bind(&timezone)
+    .fetch_one(pool)
+    .await
+    .map_err(|err| {
+        if is_unique_violation(&err) {
+            CommandError::Invalid("a user with that username or email already exists".into())
+        } else {
+            err.into()
+        }
+    })?;
+
+    println!("Created user {} ({})", user.email, user.id);
+    Ok(())
+}
+
+pub async fn reset_password(pool: &PgPool, email: String) -> Result<(), CommandError> {
+    let password = read_password()?;
+    if password.chars().count() < 8 {
+        return Err(CommandError::Invalid(
+            "password must be at least 8 characters long".into(),
+        ));
+    }
+
+    let password_hash = hash_password(&password)
+        .map_err(|err| CommandError::Invalid(format!("could not hash password: {err}")))?;
+    sqlx::query_scalar::<_, Uuid>(
+        "UPDATE users SET password_hash = $2 WHERE email = $1 RETURNING id",
+    )
+    .bind(&email)
+    .bind(&password_hash)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| CommandError::Invalid(format!("no user with email {email}")))?;
+
+    // Tokens are stateless, so ones issued before the reset stay valid until they expire
+    println!("Password updated for {email}");
+    Ok(())
+}
+
+// Deleting a habit only hides it; this makes it permanent, taking the habit's
+// completions, skips and pauses with it. Archived habits are left alone.
+pub async fn purge_deleted(
+    pool: &PgPool,
+    older_than_days: u32,
+    dry_run: bool,
+) -> Result<(), CommandError> {
+    let query = if dry_run {
+        "SELECT id, name FROM habits
+         WHERE deleted_at < NOW() - make_interval(days => $1)
+         ORDER BY deleted_at"
+    } else {
+        "DELETE FROM habits
+         WHERE deleted_at < NOW() - make_interval(days => $1)
+         RETURNING id, name"
+    };
+    let purged = sqlx::query_as::<_, (Uuid, String)>(query)
+        .bind(older_than_days as i32)
+        .fetch_all(pool)
+        .await?;
+
+    for (id, name) in &purged {
+        println!("{id}  {name}");
+    }
+    let verb = if dry_run { "Would purge" } else { "Purged" };
+    println!(
+        "{verb} {} habit(s) deleted more than {older_than_days} days ago",
+        purged.len()
+    );
+    Ok(())
+}
+
+fn check(value: &impl Validate) -> Result<(), CommandError> {
+    value.validate().map_err(|errors| {
+        let problems: Vec<String> = field_errors(&errors)
+            .into_iter()
+            .map(|error| format!("{} {}", error.field, error.message))
+            .collect();
+        CommandError::Invalid(problems.join(", "))
+    })
+}
+
+// Prompted for without echo on a terminal, otherwise one line from stdin, so
+// it can be piped in and never shows up in `ps`
+fn read_password() -> Result<String, CommandError> {
+    let password = if io::stdin().is_terminal() {
+        rpassword::prompt_password("Password: ")?
+    } else {
+        let mut password = String::new();
+        io::stdin().lock().read_line(&mut password)?;
+        password.trim_end_matches(['\r', '\n']).to_string()
+    };
+    if password.is_empty() {
+        return Err(CommandError::Invalid("no password given on stdin".into()));
+    }
+    Ok(password)
+}
@@ -0,0 +1,14 @@
+use sqlx::migrate::Migrator;
+
+// The migrations/ directory, compiled into the binary (see build.rs)
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+// Postgres error code for unique_violation, e.g. a second check-in on the same day
+const UNIQUE_VIOLATION: &str = "23505";
+
+pub fn is_unique_violation(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => db_err.code().as_deref() == Some(UNIQUE_VIOLATION),
+        _ => false,
+    }
+}
@@ -0,0 +1,165 @@
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use utoipa::ToSchema;
+
+use crate::{repo::RepoError, request_id::current_request_id};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// An RFC 7807 problem document; see the README for every `code`. A 409 may
+/// carry one extra member, e.g. `session` with the session that blocked a new one.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Only for `validation_failed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+    #[serde(flatten)]
+    #[schema(ignore)]
+    pub extra: Map<String, Value>,
+}
+
+// Every handler error. Responses are RFC 7807 problem documents whose `code`
+// member is stable for clients to match on; `detail` is for humans. Causes of
+// 5xx errors are logged, never sent to the client.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("validation failed")]
+    Validation(Vec<FieldError>),
+    #[error("{0}")]
+    Unauthorized(&'static str),
+    #[error("{0} not found")]
+    NotFound(&'static str),
+    // `context` adds extra members, e.g. the session that blocked a new one
+    #[error("{detail}")]
+    Conflict {
+        code: &'static str,
+        detail: String,
+        context: Option<(&'static str, Value)>,
+    },
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn field(field: &str, message: impl Into<String>) -> Self {
+        AppError::Validation(vec![FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        }])
+    }
+
+    pub fn conflict(code: &'static str, detail: impl Into<String>) -> Self {
+        AppError::Conflict {
+            code,
+            detail: detail.into(),
+            context: None,
+        }
+    }
+
+    pub fn with_context(self, key: &'static str, value: impl Serialize) -> Self {
+        match self {
+            AppError::Conflict { code, detail, .. } => AppError::Conflict {
+                code,
+                detail,
+                context: Some((key, json!(value))),
+            },
+            other => other,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict { .. } => StatusCode::CONFLICT,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Validation(_) => "validation_failed",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict { code, .. } => code,
+            AppError::Database(_) | AppError::Internal(_) => "internal_error",
+        }
+    }
+}
+
+// Handlers match the duplicates they expect, e.g. as already_completed, before
+// using `?`; any other is a bug
+impl From<RepoError> for AppError {
+    fn from(err: RepoError) -> Self {
+        match err {
+            RepoError::Duplicate => AppError::Internal("unexpected duplicate row".to_string()),
+            RepoError::Database(err) => AppError::Database(err),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let request_id = current_request_id();
+
+        let detail = if status.is_server_error() {
+            // Logged inside the request span, which carries the request id
+            tracing::error!(error = %self, "request failed");
+            "Something went wrong on our side".to_string()
+        } else {
+            self.to_string()
+        };
+
+        let code = self.code();
+        let (errors, extra) = match self {
+            AppError::Validation(errors) => (Some(errors), Map::new()),
+            AppError::Conflict {
+                context: Some((key, value)),
+                ..
+            } => (None, Map::from_iter([(key.to_string(), value)])),
+            _ => (None, Map::new()),
+        };
+        let body = Problem {
+            kind: "about:blank",
+            title: status.canonical_reason().unwrap_or("Error"),
+            status: status.as_u16(),
+            detail,
+            code,
+            request_id,
+            errors,
+            extra,
+        };
+
+        (
+            status,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            Json(body),
+        )
+            .into_response()
+    }
+}
@@ -4,152 +4,99 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use serde_json::json;
 use uuid::Uuid;
 
 use crate::{
     auth::middleware::AuthUser,
-    models::goal::{CreateGoal, Goal, UpdateGoal},
+    error::AppError,
+    insights::forecast::goals_with_forecast,
+    models::goal::{CreateGoal, GoalResponse, GoalsResponse, UpdateGoal},
     state::AppState,
+    timezone::{today_in, user_timezone},
+    validation::ValidatedJson,
 };
 
-pub async fn get_goals(auth: AuthUser, State(state): State<AppState>) -> impl IntoResponse {
-    let goals = sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE user_id = $1")
-        .bind(auth.user_id)
-        .fetch_all(&state.db_pool)
-        .await;
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "goals",
+    responses((status = OK, description = "Active goals carry a forecast", body = GoalsResponse))
+)]
+pub async fn get_goals(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let tz = user_timezone(&*state.repos.users, auth.user_id).await?;
+    let goals = goals_with_forecast(&*state.repos.goals, auth.user_id, tz, today_in(tz)).await?;
 
-    match goals {
-        Ok(goals) => (StatusCode::OK, Json(json!({"goals": goals}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch goals"})),
-        )
-            .into_response(),
-    }
+    Ok((StatusCode::OK, Json(GoalsResponse { goals })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "goals",
+    request_body = CreateGoal,
+    responses((status = CREATED, body = GoalResponse))
+)]
 pub async fn create_goal(
     auth: AuthUser,
     State(state): State<AppState>,
-    Json(body): Json<CreateGoal>,
-) -> impl IntoResponse {
-    let goal = sqlx::query_as::<_, Goal>(
-        "INSERT INTO goals (user_id, title, description, deadline, status) \
-         VALUES ($1, $2, $3, $4, 'active') \
-         RETURNING *",
-    )
-    .bind(auth.user_id)
-    .bind(body.title)
-    .bind(body.description)
-    .bind(body.deadline)
-    .fetch_one(&state.db_pool)
-    .await;
+    ValidatedJson(body): ValidatedJson<CreateGoal>,
+) -> Result<impl IntoResponse, AppError> {
+    let goal = state.repos.goals.create(auth.user_id, body).await?;
 
-    match goal {
-        Ok(goal) => (StatusCode::CREATED, Json(json!({"goal": goal}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to create goal"})),
-        )
-            .into_response(),
-    }
+    Ok((StatusCode::CREATED, Json(GoalResponse { goal })))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "goals",
+    request_body = UpdateGoal,
+    responses((status = OK, body = GoalResponse))
+)]
 pub async fn update_goal(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    Json(body): Json<UpdateGoal>,
-) -> impl IntoResponse {
-    let goal = sqlx::query_as::<_, Goal>(
-        "UPDATE goals
-         SET title = COALESCE($3, title),
-             description = COALESCE($4, description),
-             deadline = COALESCE($5, deadline),
-             status = COALESCE($6, status)
-         WHERE id = $1 AND user_id = $2
-         RETURNING *",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .bind(body.title)
-    .bind(body.description)
-    .bind(body.deadline)
-    .bind(body.status)
-    .fetch_optional(&state.db_pool)
-    .await;
+    ValidatedJson(body): ValidatedJson<UpdateGoal>,
+) -> Result<impl IntoResponse, AppError> {
+    let goal = state
+        .repos
+        .goals
+        .update(auth.user_id, id, body)
+        .await?
+        .ok_or(AppError::NotFound("Goal"))?;
 
-    match goal {
-        Ok(Some(goal)) => (StatusCode::OK, Json(json!({"goal": goal}))).into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Goal not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to update goal"})),
-        )
-            .into_response(),
-    }
+    Ok((StatusCode::OK, Json(GoalResponse { goal })))
 }
 
+#[utoipa::path(delete, path = "/{id}", tag = "goals", responses((status = NO_CONTENT)))]
 pub async fn delete_goal(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    let deleted = sqlx::query_scalar::<_, Uuid>(
-        "DELETE FROM goals WHERE id = $1 AND user_id = $2 RETURNING id",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match deleted {
-        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Goal not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to delete goal"})),
-        )
-            .into_response(),
+) -> Result<impl IntoResponse, AppError> {
+    if !state.repos.goals.delete(auth.user_id, id).await? {
+        return Err(AppError::NotFound("Goal"));
     }
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(post, path = "/{id}/complete", tag = "goals", responses((status = OK, body = GoalResponse)))]
 pub async fn complete_goal(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    let goal = sqlx::query_as::<_, Goal>(
-        "UPDATE goals
-         SET status = 'completed'
-         WHERE id = $1 AND user_id = $2
-         RETURNING *",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .fetch_optional(&state.db_pool)
-    .await;
+) -> Result<impl IntoResponse, AppError> {
+    let goal = state
+        .repos
+        .goals
+        .complete(auth.user_id, id)
+        .await?
+        .ok_or(AppError::NotFound("Goal"))?;
 
-    match goal {
-        Ok(Some(goal)) => (StatusCode::OK, Json(json!({"goal": goal}))).into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Goal not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to complete goal"})),
-        )
-            .into_response(),
-    }
+    Ok((StatusCode::OK, Json(GoalResponse { goal })))
 }
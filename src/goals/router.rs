@@ -1,14 +1,11 @@
-use axum::{
-    Router,
-    routing::{get, patch, post},
-};
+use utoipa_axum::routes;
 
-use crate::goals::handlers::{complete_goal, create_goal, delete_goal, get_goals, update_goal};
-use crate::state::AppState;
+use crate::goals::handlers;
+use crate::openapi::ApiRouter;
 
-pub fn goals_router() -> Router<AppState> {
-    Router::new()
-        .route("/", get(get_goals).post(create_goal))
-        .route("/{id}", patch(update_goal).delete(delete_goal))
-        .route("/{id}/complete", post(complete_goal))
+pub fn goals_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(handlers::get_goals, handlers::create_goal))
+        .routes(routes!(handlers::update_goal, handlers::delete_goal))
+        .routes(routes!(handlers::complete_goal))
 }
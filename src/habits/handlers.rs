@@ -1,161 +1,664 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
-use serde_json::json;
-use sqlx::Error;
+use chrono::{NaiveDate, Utc};
 use uuid::Uuid;
 
 use crate::{
     auth::middleware::AuthUser,
-    models::habit::{CompleteHabit, CreateHabit, Habit, HabitCompletion, UpdateHabit},
+    error::{AppError, Problem},
+    habits::history::HabitHistory,
+    habits::schedule::Schedule,
+    metrics,
+    models::habit::{
+        BackfillHabit, BackfillResponse, CompleteHabit, CompletionResponse, CompletionsResponse,
+        CreateHabit, DateRange, DueHabitsResponse, FreezeHabit, FreezeResponse, Frequency, Habit,
+        HabitFilter, HabitResponse, HabitStatsResponse, HabitWithStats, HabitsResponse, PauseHabit,
+        SkipHabit, SkipResponse, SkipsResponse, UpdateHabit,
+    },
+    repo::{RepoError, RepoResult, habits::Freeze},
     state::AppState,
+    timezone::user_today,
+    validation::ValidatedJson,
 };
 
-pub async fn get_habits(auth: AuthUser, State(state): State<AppState>) -> impl IntoResponse {
-    let habits = sqlx::query_as::<_, Habit>("SELECT * FROM habits WHERE user_id = $1")
-        .bind(auth.user_id)
-        .fetch_all(&state.db_pool)
-        .await;
+pub const FREEZE_EARN_STREAK: i64 = 7;
+pub const MAX_STREAK_FREEZES: i32 = 3;
 
-    match habits {
-        Ok(habits) => (StatusCode::OK, Json(json!({"habits": habits}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch habits"})),
-        )
-            .into_response(),
-    }
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "habits",
+    params(HabitFilter),
+    responses((status = OK, body = HabitsResponse))
+)]
+pub async fn get_habits(
+    auth: AuthUser,
+    Query(filter): Query<HabitFilter>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let habits = state
+        .repos
+        .habits
+        .list(auth.user_id, filter.include_archived)
+        .await?;
+
+    let history = HabitHistory::load(&state.repos, auth.user_id).await?;
+
+    let habits: Vec<HabitWithStats> = habits
+        .into_iter()
+        .map(|habit| {
+            let stats = history.stats(&habit);
+            HabitWithStats { habit, stats }
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(HabitsResponse { habits })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/due-today",
+    tag = "habits",
+    responses((status = OK, description = "Habits scheduled today that still need a check-in", body = DueHabitsResponse))
+)]
+pub async fn get_due_today(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let history = HabitHistory::load(&state.repos, auth.user_id).await?;
+
+    let habits = state
+        .repos
+        .habits
+        .active(auth.user_id, history.today)
+        .await?;
+
+    let due: Vec<Habit> = habits
+        .into_iter()
+        .filter(|habit| history.is_due_today(habit))
+        .collect();
+
+    Ok((StatusCode::OK, Json(DueHabitsResponse { habits: due })))
+}
+
+async fn find_habit(state: &AppState, id: Uuid, user_id: Uuid) -> Result<Habit, AppError> {
+    state
+        .repos
+        .habits
+        .find(user_id, id)
+        .await?
+        .ok_or(AppError::NotFound("Habit"))
 }
 
+#[utoipa::path(get, path = "/{id}/stats", tag = "habits", responses((status = OK, body = HabitStatsResponse)))]
+pub async fn get_habit_stats(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let habit = find_habit(&state, id, auth.user_id).await?;
+    let history = HabitHistory::load(&state.repos, auth.user_id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(HabitStatsResponse {
+            stats: history.stats(&habit),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "habits",
+    request_body = CreateHabit,
+    responses((status = CREATED, body = HabitResponse))
+)]
 pub async fn create_habit(
     auth: AuthUser,
     State(state): State<AppState>,
-    Json(body): Json<CreateHabit>,
-) -> impl IntoResponse {
-    let habit = sqlx::query_as::<_, Habit>(
-        "INSERT INTO habits (user_id, name, frequency) \
-         VALUES ($1, $2, COALESCE($3, 'daily')) \
-         RETURNING *",
+    ValidatedJson(body): ValidatedJson<CreateHabit>,
+) -> Result<impl IntoResponse, AppError> {
+    let frequency = body.frequency.unwrap_or(Frequency::Daily);
+    Schedule::new(
+        &frequency,
+        body.weekdays.as_deref(),
+        body.target_count,
+        body.interval_days,
+        Utc::now().date_naive(),
     )
-    .bind(auth.user_id)
-    .bind(body.name)
-    .bind(body.frequency)
-    .fetch_one(&state.db_pool)
-    .await;
-
-    match habit {
-        Ok(habit) => (StatusCode::CREATED, Json(json!({"habit": habit}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to create habit"})),
+    .map_err(|message| AppError::BadRequest(message.to_string()))?;
+
+    let habit = state
+        .repos
+        .habits
+        .create(
+            auth.user_id,
+            CreateHabit {
+                frequency: Some(frequency),
+                ..body
+            },
         )
-            .into_response(),
-    }
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(HabitResponse { habit })))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "habits",
+    request_body = UpdateHabit,
+    responses((status = OK, body = HabitResponse))
+)]
 pub async fn update_habit(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    Json(body): Json<UpdateHabit>,
-) -> impl IntoResponse {
-    let habit = sqlx::query_as::<_, Habit>(
-        "UPDATE habits
-         SET name = COALESCE($3, name),
-             frequency = COALESCE($4, frequency)
-         WHERE id = $1 AND user_id = $2
-         RETURNING *",
+    ValidatedJson(body): ValidatedJson<UpdateHabit>,
+) -> Result<impl IntoResponse, AppError> {
+    // Validate the schedule the habit will end up with, not just the fields sent
+    let existing = find_habit(&state, id, auth.user_id).await?;
+    Schedule::new(
+        body.frequency.as_ref().unwrap_or(&existing.frequency),
+        body.weekdays.as_deref().or(existing.weekdays.as_deref()),
+        body.target_count.or(existing.target_count),
+        body.interval_days.or(existing.interval_days),
+        Utc::now().date_naive(),
     )
-    .bind(id)
-    .bind(auth.user_id)
-    .bind(body.name)
-    .bind(body.frequency)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match habit {
-        Ok(Some(habit)) => (StatusCode::OK, Json(json!({"habit": habit}))).into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Habit not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to update habit"})),
-        )
-            .into_response(),
-    }
+    .map_err(|message| AppError::BadRequest(message.to_string()))?;
+
+    let habit = state
+        .repos
+        .habits
+        .update(auth.user_id, id, body)
+        .await?
+        .ok_or(AppError::NotFound("Habit"))?;
+
+    Ok((StatusCode::OK, Json(HabitResponse { habit })))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "habits",
+    responses((status = NO_CONTENT, description = "The habit and its history are gone; prefer archiving"))
+)]
 pub async fn delete_habit(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    let deleted = sqlx::query_scalar::<_, Uuid>(
-        "DELETE FROM habits WHERE id = $1 AND user_id = $2 RETURNING id",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match deleted {
-        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Habit not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to delete habit"})),
-        )
-            .into_response(),
+) -> Result<impl IntoResponse, AppError> {
+    if !state.repos.habits.delete(auth.user_id, id).await? {
+        return Err(AppError::NotFound("Habit"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Keeping a streak going for every FREEZE_EARN_STREAK periods earns one more
+// freeze, up to MAX_STREAK_FREEZES. Only same-day check-ins count toward the
+// streak, and each period pays out once, so neither backdating nor undoing
+// and redoing a check-in can be used to farm freezes.
+async fn award_streak_freeze(state: &AppState, habit: &Habit, user_id: Uuid) -> RepoResult<bool> {
+    let history = HabitHistory::load(&state.repos, user_id).await?;
+    let streak = history.earning_streak(habit);
+    if streak == 0 || streak % FREEZE_EARN_STREAK != 0 {
+        return Ok(false);
     }
+
+    state
+        .repos
+        .habits
+        .award_streak_freeze(
+            user_id,
+            habit.id,
+            history.current_period(habit),
+            MAX_STREAK_FREEZES,
+        )
+        .await
 }
 
+#[utoipa::path(
+    post,
+    path = "/{id}/complete",
+    tag = "habits",
+    request_body = CompleteHabit,
+    responses(
+        (status = OK, body = CompletionResponse),
+        (status = CONFLICT, description = "`already_completed`", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 pub async fn complete_habit(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    Json(body): Json<CompleteHabit>,
-) -> impl IntoResponse {
-    let completion = sqlx::query_as::<_, HabitCompletion>(
-        "INSERT INTO habit_completions (habit_id, completed_on)
-         SELECT id, COALESCE($2, CURRENT_DATE)
-         FROM habits
-         WHERE id = $1 AND user_id = $3
-         RETURNING *",
+    ValidatedJson(body): ValidatedJson<CompleteHabit>,
+) -> Result<impl IntoResponse, AppError> {
+    let habit = find_habit(&state, id, auth.user_id).await?;
+
+    // Default to the user's local date rather than the database server's
+    let today = user_today(&*state.repos.users, auth.user_id).await?;
+    let completed_on = body.completed_on.unwrap_or(today);
+    let same_day = completed_on == today;
+
+    let completion = if habit.daily_target.is_some() {
+        let value = match body.value {
+            Some(value) if value > 0.0 => value,
+            _ => {
+                return Err(AppError::field(
+                    "value",
+                    "a positive value is required for measured habits",
+                ));
+            }
+        };
+
+        // Partial entries add up into the day's single row
+        state
+            .repos
+            .habits
+            .add_measurement(auth.user_id, habit.id, completed_on, value, same_day)
+            .await
+    } else {
+        state
+            .repos
+            .habits
+            .add_completion(auth.user_id, habit.id, completed_on, None, same_day)
+            .await
+    };
+
+    let completion = match completion {
+        Ok(Some(completion)) => completion,
+        Ok(None) => return Err(AppError::NotFound("Habit")),
+        Err(RepoError::Duplicate) => {
+            return Err(AppError::conflict(
+                "already_completed",
+                "Habit already completed for that date",
+            ));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    metrics::habit_checked_in("check_in", 1);
+
+    // Partial entries only matter for earning once they push the day over its target
+    let completes_day = match (habit.daily_target, body.value) {
+        (Some(target), Some(value)) => {
+            let total = completion.value.unwrap_or(0.0);
+            total >= target && total - value < target
+        }
+        _ => true,
+    };
+    let freeze_earned = same_day
+        && completes_day
+        && award_streak_freeze(&state, &habit, auth.user_id)
+            .await
+            .unwrap_or(false);
+
+    Ok((
+        StatusCode::OK,
+        Json(CompletionResponse {
+            completion,
+            freeze_earned,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/completions",
+    tag = "habits",
+    params(DateRange),
+    responses((status = OK, body = CompletionsResponse))
+)]
+pub async fn get_completions(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Query(range): Query<DateRange>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    find_habit(&state, id, auth.user_id).await?;
+
+    let completions = state
+        .repos
+        .habits
+        .completions(auth.user_id, id, range.from, range.to)
+        .await?;
+
+    Ok((StatusCode::OK, Json(CompletionsResponse { completions })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}/completions/{date}",
+    tag = "habits",
+    responses((status = NO_CONTENT))
+)]
+pub async fn delete_completion(
+    auth: AuthUser,
+    Path((id, date)): Path<(Uuid, NaiveDate)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    if !state
+        .repos
+        .habits
+        .delete_completion(auth.user_id, id, date)
+        .await?
+    {
+        return Err(AppError::NotFound("Completion"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/completions",
+    tag = "habits",
+    request_body = BackfillHabit,
+    responses((status = OK, body = BackfillResponse))
+)]
+pub async fn backfill_completions(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<BackfillHabit>,
+) -> Result<impl IntoResponse, AppError> {
+    let habit = find_habit(&state, id, auth.user_id).await?;
+
+    let mut dates = body.dates;
+    dates.sort();
+    dates.dedup();
+
+    // Each date is inserted on its own so a duplicate only skips that one date.
+    // Backfilled days of a measured habit are recorded as having hit the
+    // target, so a day that already has a partial measurement is topped up.
+    let mut inserted = Vec::new();
+    let mut topped_up = Vec::new();
+    let mut already_present = Vec::new();
+    for date in dates {
+        let result = state
+            .repos
+            .habits
+            .add_completion(auth.user_id, id, date, habit.daily_target, false)
+            .await;
+
+        match result {
+            Ok(Some(_)) => inserted.push(date),
+            Ok(None) => return Err(AppError::NotFound("Habit")),
+            Err(RepoError::Duplicate) => {
+                if top_up(&state, auth.user_id, &habit, date).await? {
+                    topped_up.push(date);
+                } else {
+                    already_present.push(date);
+                }
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    metrics::habit_checked_in("backfill", (inserted.len() + topped_up.len()) as u64);
+
+    Ok((
+        StatusCode::OK,
+        Json(BackfillResponse {
+            inserted,
+            topped_up,
+            already_present,
+        }),
+    ))
+}
+
+// Brings a measured day that's below the daily target up to it; false when
+// the habit isn't measured or the day already reached it
+async fn top_up(
+    state: &AppState,
+    user_id: Uuid,
+    habit: &Habit,
+    date: NaiveDate,
+) -> Result<bool, AppError> {
+    let Some(target) = habit.daily_target else {
+        return Ok(false);
+    };
+    let logged: f64 = state
+        .repos
+        .habits
+        .completions(user_id, habit.id, Some(date), Some(date))
+        .await?
+        .iter()
+        .filter_map(|c| c.value)
+        .sum();
+    if logged >= target {
+        return Ok(false);
+    }
+    state
+        .repos
+        .habits
+        .add_measurement(user_id, habit.id, date, target - logged, false)
+        .await?
+        .ok_or(AppError::NotFound("Habit"))?;
+    Ok(true)
+}
+
+// Resolves an optional date from the request body to the user's local today
+async fn date_or_today(
+    state: &AppState,
+    user_id: Uuid,
+    date: Option<NaiveDate>,
+) -> RepoResult<NaiveDate> {
+    match date {
+        Some(date) => Ok(date),
+        None => user_today(&*state.repos.users, user_id).await,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/skips",
+    tag = "habits",
+    params(DateRange),
+    responses((status = OK, body = SkipsResponse))
+)]
+pub async fn get_skips(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Query(range): Query<DateRange>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    find_habit(&state, id, auth.user_id).await?;
+
+    let skips = state
+        .repos
+        .habits
+        .skips(auth.user_id, id, range.from, range.to)
+        .await?;
+
+    Ok((StatusCode::OK, Json(SkipsResponse { skips })))
+}
+
+fn already_skipped() -> AppError {
+    AppError::conflict("already_skipped", "Habit already skipped for that date")
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/skips",
+    tag = "habits",
+    request_body = SkipHabit,
+    responses(
+        (status = CREATED, body = SkipResponse),
+        (status = CONFLICT, description = "`already_skipped`", body = Problem, content_type = "application/problem+json"),
     )
-    .bind(id)
-    .bind(body.completed_on)
-    .bind(auth.user_id)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match completion {
-        Ok(Some(completion)) => {
-            (StatusCode::OK, Json(json!({"completion": completion}))).into_response()
+)]
+pub async fn skip_habit(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<SkipHabit>,
+) -> Result<impl IntoResponse, AppError> {
+    // Skips are planned ahead; making up for a missed day costs a freeze
+    let today = user_today(&*state.repos.users, auth.user_id).await?;
+    let skipped_on = body.skipped_on.unwrap_or(today);
+    if skipped_on < today {
+        return Err(AppError::field(
+            "skipped_on",
+            "must not be in the past; spend a streak freeze on a missed day",
+        ));
+    }
+
+    let skip = state
+        .repos
+        .habits
+        .skip(auth.user_id, id, skipped_on, body.note)
+        .await;
+
+    match skip {
+        Ok(Some(skip)) => Ok((StatusCode::CREATED, Json(SkipResponse { skip }))),
+        Ok(None) => Err(AppError::NotFound("Habit")),
+        Err(RepoError::Duplicate) => Err(already_skipped()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// Spends one of the user's streak freezes to excuse a missed day
+#[utoipa::path(
+    post,
+    path = "/{id}/freeze",
+    tag = "habits",
+    request_body = FreezeHabit,
+    responses(
+        (status = CREATED, body = FreezeResponse),
+        (status = CONFLICT, description = "`no_streak_freezes` or `already_skipped`", body = Problem,
+         content_type = "application/problem+json"),
+    )
+)]
+pub async fn freeze_habit(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<FreezeHabit>,
+) -> Result<impl IntoResponse, AppError> {
+    find_habit(&state, id, auth.user_id).await?;
+    let frozen_on = date_or_today(&state, auth.user_id, body.frozen_on).await?;
+
+    let Freeze {
+        skip,
+        streak_freezes,
+    } = match state.repos.habits.freeze(auth.user_id, id, frozen_on).await {
+        Ok(Some(freeze)) => freeze,
+        Ok(None) => {
+            return Err(AppError::conflict(
+                "no_streak_freezes",
+                "No streak freezes left",
+            ));
         }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Habit not found"})),
-        )
-            .into_response(),
-        Err(Error::Database(db_err)) if db_err.code().as_deref() == Some("23505") => (
-            StatusCode::CONFLICT,
-            Json(json!({"error": "Habit already completed for that date"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to complete habit"})),
-        )
-            .into_response(),
+        Err(RepoError::Duplicate) => return Err(already_skipped()),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok((
+        StatusCode::CREATED,
+        Json(FreezeResponse {
+            skip,
+            streak_freezes,
+        }),
+    ))
+}
+
+// Removing a freeze gives it back to the user, up to MAX_STREAK_FREEZES
+#[utoipa::path(delete, path = "/{id}/skips/{date}", tag = "habits", responses((status = NO_CONTENT)))]
+pub async fn delete_skip(
+    auth: AuthUser,
+    Path((id, date)): Path<(Uuid, NaiveDate)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    if !state
+        .repos
+        .habits
+        .delete_skip(auth.user_id, id, date, MAX_STREAK_FREEZES)
+        .await?
+    {
+        return Err(AppError::NotFound("Skip"));
     }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(post, path = "/{id}/archive", tag = "habits", responses((status = OK, body = HabitResponse)))]
+pub async fn archive_habit(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let habit = state
+        .repos
+        .habits
+        .set_archived(auth.user_id, id, true)
+        .await?
+        .ok_or(AppError::NotFound("Habit"))?;
+
+    Ok((StatusCode::OK, Json(HabitResponse { habit })))
+}
+
+#[utoipa::path(post, path = "/{id}/unarchive", tag = "habits", responses((status = OK, body = HabitResponse)))]
+pub async fn unarchive_habit(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let habit = state
+        .repos
+        .habits
+        .set_archived(auth.user_id, id, false)
+        .await?
+        .ok_or(AppError::NotFound("Habit"))?;
+
+    Ok((StatusCode::OK, Json(HabitResponse { habit })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/pause",
+    tag = "habits",
+    request_body = PauseHabit,
+    responses((status = OK, body = HabitResponse))
+)]
+pub async fn pause_habit(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<PauseHabit>,
+) -> Result<impl IntoResponse, AppError> {
+    let today = user_today(&*state.repos.users, auth.user_id).await?;
+    if body.until < today {
+        return Err(AppError::field("until", "must not be in the past"));
+    }
+
+    let habit = state
+        .repos
+        .habits
+        .pause(auth.user_id, id, today, body.until)
+        .await?
+        .ok_or(AppError::NotFound("Habit"))?;
+
+    Ok((StatusCode::OK, Json(HabitResponse { habit })))
+}
+
+#[utoipa::path(post, path = "/{id}/resume", tag = "habits", responses((status = OK, body = HabitResponse)))]
+pub async fn resume_habit(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let today = user_today(&*state.repos.users, auth.user_id).await?;
+    let habit = state
+        .repos
+        .habits
+        .resume(auth.user_id, id, today)
+        .await?
+        .ok_or(AppError::NotFound("Habit"))?;
+
+    Ok((StatusCode::OK, Json(HabitResponse { habit })))
 }
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    habits::{
+        schedule::Schedule,
+        stats::{NeutralDays, compute_stats, is_due_today},
+    },
+    models::{
+        habit::{Habit, HabitCompletion, HabitPause, HabitSkip, HabitStats},
+        vacation::Vacation,
+    },
+    repo::{RepoResult, Repos},
+    timezone::user_timezone,
+};
+
+// Everything streak and due-today calculations need for one user's habits
+pub struct HabitHistory {
+    pub tz: Tz,
+    pub today: NaiveDate,
+    completions: HashMap<Uuid, Vec<HabitCompletion>>,
+    skips: HashMap<Uuid, Vec<HabitSkip>>,
+    pauses: HashMap<Uuid, Vec<HabitPause>>,
+    vacations: Vec<Vacation>,
+}
+
+impl HabitHistory {
+    pub async fn load(repos: &Repos, user_id: Uuid) -> RepoResult<Self> {
+        let completions = repos.habits.user_completions(user_id).await?;
+        let skips = repos.habits.user_skips(user_id).await?;
+        let pauses = repos.habits.user_pauses(user_id).await?;
+        let vacations = repos.habits.vacations(user_id).await?;
+        let tz = user_timezone(&*repos.users, user_id).await?;
+
+        let mut by_habit: HashMap<Uuid, Vec<HabitCompletion>> = HashMap::new();
+        for completion in completions {
+            by_habit
+                .entry(completion.habit_id)
+                .or_default()
+                .push(completion);
+        }
+        let mut skips_by_habit: HashMap<Uuid, Vec<HabitSkip>> = HashMap::new();
+        for skip in skips {
+            skips_by_habit.entry(skip.habit_id).or_default().push(skip);
+        }
+        let mut pauses_by_habit: HashMap<Uuid, Vec<HabitPause>> = HashMap::new();
+        for pause in pauses {
+            pauses_by_habit
+                .entry(pause.habit_id)
+                .or_default()
+                .push(pause);
+        }
+
+        Ok(Self {
+            tz,
+            today: Utc::now().with_timezone(&tz).date_naive(),
+            completions: by_habit,
+            skips: skips_by_habit,
+            pauses: pauses_by_habit,
+            vacations,
+        })
+    }
+
+    fn completions(&self, habit: &Habit) -> &[HabitCompletion] {
+        self.completions.get(&habit.id).map_or(&[], Vec::as_slice)
+    }
+
+    fn neutral_days(&self, habit: &Habit) -> NeutralDays<'_> {
+        let skips = self.skips.get(&habit.id).map_or(&[][..], Vec::as_slice);
+        let pauses = self.pauses.get(&habit.id).map_or(&[][..], Vec::as_slice);
+        NeutralDays::new(skips, pauses, &self.vacations)
+    }
+
+    pub fn stats(&self, habit: &Habit) -> HabitStats {
+        compute_stats(
+            habit,
+            self.tz,
+            self.completions(habit),
+            &self.neutral_days(habit),
+            self.today,
+        )
+    }
+
+    // The current streak counting only check-ins made on their own day, which
+    // is what earns freezes; backfilled and backdated days don't
+    pub fn earning_streak(&self, habit: &Habit) -> i64 {
+        let same_day: Vec<HabitCompletion> = self
+            .completions(habit)
+            .iter()
+            .filter(|c| c.same_day)
+            .cloned()
+            .collect();
+        compute_stats(
+            habit,
+            self.tz,
+            &same_day,
+            &self.neutral_days(habit),
+            self.today,
+        )
+        .current_streak
+    }
+
+    // First day of the habit's schedule period in progress today
+    pub fn current_period(&self, habit: &Habit) -> NaiveDate {
+        let created_on = habit.created_at.with_timezone(&self.tz).date_naive();
+        Schedule::for_habit(habit, created_on).current_period(self.today)
+    }
+
+    pub fn is_due_today(&self, habit: &Habit) -> bool {
+        is_due_today(
+            habit,
+            self.tz,
+            self.completions(habit),
+            &self.neutral_days(habit),
+            self.today,
+        )
+    }
+}
@@ -1,2 +1,5 @@
 pub mod handlers;
+pub mod history;
 pub mod router;
+pub mod schedule;
+pub mod stats;
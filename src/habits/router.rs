@@ -1,16 +1,25 @@
-use axum::{
-    Router,
-    routing::{get, patch, post},
-};
+use utoipa_axum::routes;
 
-use crate::habits::handlers::{
-    complete_habit, create_habit, delete_habit, get_habits, update_habit,
-};
-use crate::state::AppState;
+use crate::habits::handlers;
+use crate::openapi::ApiRouter;
 
-pub fn habits_router() -> Router<AppState> {
-    Router::new()
-        .route("/", get(get_habits).post(create_habit))
-        .route("/{id}", patch(update_habit).delete(delete_habit))
-        .route("/{id}/complete", post(complete_habit))
+pub fn habits_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(handlers::get_habits, handlers::create_habit))
+        .routes(routes!(handlers::get_due_today))
+        .routes(routes!(handlers::update_habit, handlers::delete_habit))
+        .routes(routes!(handlers::complete_habit))
+        .routes(routes!(handlers::get_habit_stats))
+        .routes(routes!(
+            handlers::get_completions,
+            handlers::backfill_completions
+        ))
+        .routes(routes!(handlers::delete_completion))
+        .routes(routes!(handlers::get_skips, handlers::skip_habit))
+        .routes(routes!(handlers::delete_skip))
+        .routes(routes!(handlers::freeze_habit))
+        .routes(routes!(handlers::archive_habit))
+        .routes(routes!(handlers::unarchive_habit))
+        .routes(routes!(handlers::pause_habit))
+        .routes(routes!(handlers::resume_habit))
 }
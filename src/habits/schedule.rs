@@ -0,0 +1,243 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use crate::models::habit::{Frequency, Habit};
+
+// A habit's frequency resolved into periods, each needing `target()` check-ins
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Daily,
+    Weekly,
+    Weekdays(Vec<Weekday>),
+    TimesPerWeek(u32),
+    TimesPerMonth(u32),
+    EveryNDays { interval: i64, anchor: NaiveDate },
+}
+
+impl Schedule {
+    // `anchor` is the first day of the first block for every_n_days habits
+    pub fn new(
+        frequency: &Frequency,
+        weekdays: Option<&[i16]>,
+        target_count: Option<i32>,
+        interval_days: Option<i32>,
+        anchor: NaiveDate,
+    ) -> Result<Self, &'static str> {
+        match frequency {
+            Frequency::Daily => Ok(Schedule::Daily),
+            Frequency::Weekly => Ok(Schedule::Weekly),
+            Frequency::Weekdays => {
+                let days = weekdays.unwrap_or_default();
+                if days.is_empty() {
+                    return Err("weekdays must list at least one day");
+                }
+                days.iter()
+                    .map(|&day| iso_weekday(day))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Schedule::Weekdays)
+                    .ok_or("weekdays must be between 1 (Monday) and 7 (Sunday)")
+            }
+            Frequency::TimesPerWeek => match target_count {
+                Some(n @ 1..=7) => Ok(Schedule::TimesPerWeek(n as u32)),
+                _ => Err("target_count must be between 1 and 7 for times_per_week"),
+            },
+            Frequency::TimesPerMonth => match target_count {
+                Some(n @ 1..=31) => Ok(Schedule::TimesPerMonth(n as u32)),
+                _ => Err("target_count must be between 1 and 31 for times_per_month"),
+            },
+            Frequency::EveryNDays => match interval_days {
+                Some(n) if n >= 1 => Ok(Schedule::EveryNDays {
+                    interval: n as i64,
+                    anchor,
+                }),
+                _ => Err("interval_days must be at least 1 for every_n_days"),
+            },
+        }
+    }
+
+    // Stored habits were validated on write; fall back to daily rather than fail a read
+    pub fn for_habit(habit: &Habit, created_on: NaiveDate) -> Self {
+        Self::new(
+            &habit.frequency,
+            habit.weekdays.as_deref(),
+            habit.target_count,
+            habit.interval_days,
+            created_on,
+        )
+        .unwrap_or(Schedule::Daily)
+    }
+
+    pub fn target(&self) -> u32 {
+        match self {
+            Schedule::TimesPerWeek(n) | Schedule::TimesPerMonth(n) => *n,
+            _ => 1,
+        }
+    }
+
+    // First day of the period containing `date`, or None if the date isn't scheduled
+    pub fn period_start(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Schedule::Daily => Some(date),
+            Schedule::Weekly | Schedule::TimesPerWeek(_) => {
+                Some(date - Duration::days(date.weekday().num_days_from_monday() as i64))
+            }
+            Schedule::TimesPerMonth(_) => date.with_day(1),
+            Schedule::Weekdays(days) => days.contains(&date.weekday()).then_some(date),
+            Schedule::EveryNDays { interval, anchor } => {
+                let blocks = (date - *anchor).num_days().div_euclid(*interval);
+                Some(*anchor + Duration::days(blocks * interval))
+            }
+        }
+    }
+
+    // The period in progress on `date`; on an unscheduled weekday that's the next scheduled day
+    pub fn current_period(&self, date: NaiveDate) -> NaiveDate {
+        self.period_start(date).unwrap_or_else(|| self.next(date))
+    }
+
+    pub fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Schedule::Daily => start + Duration::days(1),
+            Schedule::Weekly | Schedule::TimesPerWeek(_) => start + Duration::weeks(1),
+            Schedule::TimesPerMonth(_) => start + Months::new(1),
+            Schedule::Weekdays(days) => {
+                let mut day = start + Duration::days(1);
+                while !days.contains(&day.weekday()) {
+                    day += Duration::days(1);
+                }
+                day
+            }
+            Schedule::EveryNDays { interval, .. } => start + Duration::days(*interval),
+        }
+    }
+
+    // Exclusive end of the period; a weekday habit's period is just its scheduled day
+    pub fn period_end(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Schedule::Weekdays(_) => start + Duration::days(1),
+            _ => self.next(start),
+        }
+    }
+}
+
+fn iso_weekday(day: i16) -> Option<Weekday> {
+    match day {
+        1 => Some(Weekday::Mon),
+        2 => Some(Weekday::Tue),
+        3 => Some(Weekday::Wed),
+        4 => Some(Weekday::Thu),
+        5 => Some(Weekday::Fri),
+        6 => Some(Weekday::Sat),
+        7 => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn schedule(frequency: Frequency, weekdays: Option<&[i16]>, target: Option<i32>) -> Schedule {
+        Schedule::new(&frequency, weekdays, target, Some(3), date("2026-03-01")).unwrap()
+    }
+
+    #[test]
+    fn rejects_incomplete_schedules() {
+        let anchor = date("2026-03-01");
+        let new = |frequency, weekdays, target, interval| {
+            Schedule::new(&frequency, weekdays, target, interval, anchor)
+        };
+        assert!(new(Frequency::Weekdays, None, None, None).is_err());
+        assert!(new(Frequency::Weekdays, Some(&[0]), None, None).is_err());
+        assert!(new(Frequency::Weekdays, Some(&[8]), None, None).is_err());
+        assert!(new(Frequency::TimesPerWeek, None, Some(8), None).is_err());
+        assert!(new(Frequency::TimesPerWeek, None, Some(0), None).is_err());
+        assert!(new(Frequency::TimesPerMonth, None, Some(32), None).is_err());
+        assert!(new(Frequency::EveryNDays, None, None, Some(0)).is_err());
+        assert!(new(Frequency::EveryNDays, None, None, None).is_err());
+    }
+
+    #[test]
+    fn weekdays_are_one_day_periods() {
+        // Monday, Wednesday, Friday
+        let schedule = schedule(Frequency::Weekdays, Some(&[1, 3, 5]), None);
+        assert_eq!(schedule.target(), 1);
+        assert_eq!(
+            schedule.period_start(date("2026-03-04")),
+            Some(date("2026-03-04"))
+        );
+        assert_eq!(schedule.period_start(date("2026-03-03")), None);
+        assert_eq!(
+            schedule.current_period(date("2026-03-03")),
+            date("2026-03-04")
+        );
+        assert_eq!(schedule.period_end(date("2026-03-04")), date("2026-03-05"));
+        // Friday's next period wraps over the weekend
+        assert_eq!(schedule.next(date("2026-03-06")), date("2026-03-09"));
+        assert_eq!(
+            schedule.current_period(date("2026-03-07")),
+            date("2026-03-09")
+        );
+    }
+
+    #[test]
+    fn times_per_week_runs_monday_to_sunday() {
+        let schedule = schedule(Frequency::TimesPerWeek, None, Some(3));
+        assert_eq!(schedule.target(), 3);
+        assert_eq!(
+            schedule.period_start(date("2026-03-02")),
+            Some(date("2026-03-02"))
+        );
+        assert_eq!(
+            schedule.period_start(date("2026-03-08")),
+            Some(date("2026-03-02"))
+        );
+        assert_eq!(
+            schedule.period_start(date("2026-03-09")),
+            Some(date("2026-03-09"))
+        );
+        assert_eq!(schedule.period_end(date("2026-03-02")), date("2026-03-09"));
+    }
+
+    #[test]
+    fn times_per_month_follows_month_lengths() {
+        let schedule = schedule(Frequency::TimesPerMonth, None, Some(10));
+        assert_eq!(schedule.target(), 10);
+        assert_eq!(
+            schedule.period_start(date("2024-02-29")),
+            Some(date("2024-02-01"))
+        );
+        assert_eq!(
+            schedule.period_start(date("2026-01-31")),
+            Some(date("2026-01-01"))
+        );
+        assert_eq!(schedule.period_end(date("2024-02-01")), date("2024-03-01"));
+        assert_eq!(schedule.period_end(date("2026-02-01")), date("2026-03-01"));
+        assert_eq!(schedule.next(date("2026-01-01")), date("2026-02-01"));
+        assert_eq!(schedule.next(date("2026-12-01")), date("2027-01-01"));
+    }
+
+    #[test]
+    fn every_n_days_counts_blocks_from_the_anchor() {
+        // Three-day blocks from Sunday 2026-03-01
+        let schedule = schedule(Frequency::EveryNDays, None, None);
+        assert_eq!(schedule.target(), 1);
+        assert_eq!(
+            schedule.period_start(date("2026-03-03")),
+            Some(date("2026-03-01"))
+        );
+        assert_eq!(
+            schedule.period_start(date("2026-03-04")),
+            Some(date("2026-03-04"))
+        );
+        // Backfilled days before the anchor fall into earlier blocks
+        assert_eq!(
+            schedule.period_start(date("2026-02-28")),
+            Some(date("2026-02-26"))
+        );
+        assert_eq!(schedule.period_end(date("2026-03-04")), date("2026-03-07"));
+    }
+}
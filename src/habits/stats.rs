@@ -0,0 +1,406 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::habits::schedule::Schedule;
+use crate::models::habit::{Habit, HabitCompletion, HabitPause, HabitSkip, HabitStats};
+use crate::models::vacation::Vacation;
+
+// Days that neither keep nor break a streak: the habit's skipped, frozen and
+// paused days plus the user's vacations
+pub struct NeutralDays<'a> {
+    skipped: HashSet<NaiveDate>,
+    pauses: &'a [HabitPause],
+    vacations: &'a [Vacation],
+}
+
+impl<'a> NeutralDays<'a> {
+    pub fn new(skips: &[HabitSkip], pauses: &'a [HabitPause], vacations: &'a [Vacation]) -> Self {
+        Self {
+            skipped: skips.iter().map(|skip| skip.skipped_on).collect(),
+            pauses,
+            vacations,
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.skipped.contains(&date)
+            || self
+                .pauses
+                .iter()
+                .any(|p| p.starts_on <= date && date <= p.ends_on)
+            || self
+                .vacations
+                .iter()
+                .any(|v| v.starts_on <= date && date <= v.ends_on)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Done,
+    Missed,
+    // Unfinished but excused by too many neutral days, or still in progress
+    Neutral,
+}
+
+// Days that count as done: any check-in for plain habits, reaching the daily
+// target for measured ones
+fn completed_days(habit: &Habit, completions: &[HabitCompletion]) -> Vec<NaiveDate> {
+    completions
+        .iter()
+        .filter(|c| match habit.daily_target {
+            Some(target) => c.value.unwrap_or(0.0) >= target,
+            None => true,
+        })
+        .map(|c| c.completed_on)
+        .collect()
+}
+
+// Check-ins per scheduled period; check-ins on unscheduled days or in the future are ignored
+fn period_counts(
+    schedule: &Schedule,
+    completions: &[NaiveDate],
+    today: NaiveDate,
+) -> HashMap<NaiveDate, u32> {
+    let mut counts = HashMap::new();
+    for date in completions.iter().filter(|date| **date <= today) {
+        if let Some(start) = schedule.period_start(*date) {
+            *counts.entry(start).or_default() += 1;
+        }
+    }
+    counts
+}
+
+// `today` must already be in the user's timezone; `tz` is only used to place
+// the habit's creation timestamp on the user's calendar.
+pub fn compute_stats(
+    habit: &Habit,
+    tz: Tz,
+    entries: &[HabitCompletion],
+    neutral: &NeutralDays,
+    today: NaiveDate,
+) -> HabitStats {
+    let completions = &completed_days(habit, entries)[..];
+    let created_on = habit.created_at.with_timezone(&tz).date_naive();
+    let schedule = Schedule::for_habit(habit, created_on);
+    let current = schedule.current_period(today);
+    let counts = period_counts(&schedule, completions, today);
+
+    // Backfilled check-ins may predate the habit itself
+    let first_day = entries
+        .iter()
+        .map(|c| c.completed_on)
+        .min()
+        .map_or(created_on, |earliest| earliest.min(created_on));
+
+    // Every scheduled period from the first tracked day up to the current one
+    let mut periods = Vec::new();
+    let mut period = schedule.current_period(first_day);
+    while period <= current {
+        let done = counts.get(&period).copied().unwrap_or(0) >= schedule.target();
+        // Neutral days only excuse a period when too few days are left to
+        // reach its target, so one skip can't rescue a short week or month
+        let excused = || {
+            let mut open_days = 0;
+            let mut day = period;
+            while day < schedule.period_end(period) {
+                if !neutral.contains(day) {
+                    open_days += 1;
+                }
+                day += Duration::days(1);
+            }
+            open_days < schedule.target()
+        };
+        let outcome = if done {
+            Outcome::Done
+        } else if period == current || excused() {
+            Outcome::Neutral
+        } else {
+            Outcome::Missed
+        };
+        periods.push((period, outcome));
+        period = schedule.next(period);
+    }
+
+    let current_streak = periods
+        .iter()
+        .rev()
+        .take_while(|(_, outcome)| *outcome != Outcome::Missed)
+        .filter(|(_, outcome)| *outcome == Outcome::Done)
+        .count() as i64;
+
+    let mut longest_streak = 0;
+    let mut run = 0;
+    for (_, outcome) in &periods {
+        match outcome {
+            Outcome::Done => {
+                run += 1;
+                longest_streak = longest_streak.max(run);
+            }
+            Outcome::Missed => run = 0,
+            Outcome::Neutral => {}
+        }
+    }
+
+    let completion_rate = |days: i64| {
+        let window_start = schedule.current_period(today - Duration::days(days - 1));
+        let (mut done, mut total) = (0, 0);
+        for (_, outcome) in periods.iter().filter(|(start, _)| *start >= window_start) {
+            match outcome {
+                Outcome::Done => {
+                    done += 1;
+                    total += 1;
+                }
+                Outcome::Missed => total += 1,
+                Outcome::Neutral => {}
+            }
+        }
+        if total == 0 {
+            0.0
+        } else {
+            done as f64 / total as f64
+        }
+    };
+
+    // Totals count what has happened so far, like the streaks
+    let (total_value, average_value) = match habit.daily_target {
+        Some(_) => {
+            let past: Vec<_> = entries.iter().filter(|c| c.completed_on <= today).collect();
+            let total: f64 = past.iter().filter_map(|c| c.value).sum();
+            let average = (!past.is_empty()).then(|| total / past.len() as f64);
+            (Some(total), average)
+        }
+        None => (None, None),
+    };
+
+    HabitStats {
+        current_streak,
+        longest_streak,
+        completion_rate_7d: completion_rate(7),
+        completion_rate_30d: completion_rate(30),
+        completion_rate_365d: completion_rate(365),
+        total_completions: completions.iter().filter(|day| **day <= today).count() as i64,
+        total_value,
+        average_value,
+    }
+}
+
+// Due when today is a scheduled, non-neutral day, the current period still
+// needs check-ins and the habit hasn't already been completed today.
+pub fn is_due_today(
+    habit: &Habit,
+    tz: Tz,
+    entries: &[HabitCompletion],
+    neutral: &NeutralDays,
+    today: NaiveDate,
+) -> bool {
+    let completions = &completed_days(habit, entries)[..];
+    let created_on = habit.created_at.with_timezone(&tz).date_naive();
+    let schedule = Schedule::for_habit(habit, created_on);
+
+    let Some(current) = schedule.period_start(today) else {
+        return false;
+    };
+    if completions.contains(&today) || neutral.contains(today) {
+        return false;
+    }
+
+    let done = period_counts(&schedule, completions, today)
+        .get(&current)
+        .copied()
+        .unwrap_or(0);
+    done < schedule.target()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveTime, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::habit::{Frequency, SkipKind};
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn habit(frequency: Frequency, created_on: &str) -> Habit {
+        Habit {
+            id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            name: "Habit".to_string(),
+            frequency,
+            created_at: date(created_on).and_time(NaiveTime::MIN).and_utc(),
+            weekdays: None,
+            target_count: None,
+            interval_days: None,
+            unit: None,
+            daily_target: None,
+            archived_at: None,
+            paused_until: None,
+        }
+    }
+
+    fn completions(days: &[&str]) -> Vec<HabitCompletion> {
+        days.iter()
+            .map(|day| HabitCompletion {
+                id: Uuid::new_v4(),
+                habit_id: Uuid::nil(),
+                completed_on: date(day),
+                value: None,
+                same_day: true,
+            })
+            .collect()
+    }
+
+    fn skips(days: &[&str]) -> Vec<HabitSkip> {
+        days.iter()
+            .map(|day| HabitSkip {
+                id: Uuid::new_v4(),
+                habit_id: Uuid::nil(),
+                skipped_on: date(day),
+                kind: SkipKind::Skip,
+                note: None,
+                created_at: Utc::now(),
+            })
+            .collect()
+    }
+
+    // Current streak with `skipped` days excused
+    fn streak(habit: &Habit, done: &[&str], skipped: &[&str], today: &str) -> i64 {
+        let skips = skips(skipped);
+        let neutral = NeutralDays::new(&skips, &[], &[]);
+        compute_stats(habit, Tz::UTC, &completions(done), &neutral, date(today)).current_streak
+    }
+
+    #[test]
+    fn weekdays_only_count_scheduled_days() {
+        // Monday, Wednesday, Friday from Monday 2026-03-02
+        let habit = Habit {
+            weekdays: Some(vec![1, 3, 5]),
+            ..habit(Frequency::Weekdays, "2026-03-02")
+        };
+        let today = "2026-03-10";
+
+        // Tuesday's check-in is off schedule and neither helps nor hurts
+        let all = [
+            "2026-03-02",
+            "2026-03-03",
+            "2026-03-04",
+            "2026-03-06",
+            "2026-03-09",
+        ];
+        assert_eq!(streak(&habit, &all, &[], today), 4);
+
+        let missed_wednesday = ["2026-03-02", "2026-03-06", "2026-03-09"];
+        assert_eq!(streak(&habit, &missed_wednesday, &[], today), 2);
+        assert_eq!(streak(&habit, &missed_wednesday, &["2026-03-04"], today), 3);
+    }
+
+    #[test]
+    fn times_per_week_judges_whole_weeks() {
+        let habit = Habit {
+            target_count: Some(3),
+            ..habit(Frequency::TimesPerWeek, "2026-03-02")
+        };
+        let today = "2026-03-18";
+        // Sunday 2026-03-08 still belongs to the first week
+        let first_week = ["2026-03-02", "2026-03-05", "2026-03-08"];
+
+        // The current week is in progress and doesn't break the streak yet
+        let mut done = first_week.to_vec();
+        done.extend(["2026-03-09", "2026-03-10", "2026-03-15", "2026-03-16"]);
+        assert_eq!(streak(&habit, &done, &[], today), 2);
+
+        // Two of three in the second week misses it; skipping a day still
+        // leaves enough days for a third check-in
+        let mut short = first_week.to_vec();
+        short.extend(["2026-03-09", "2026-03-10"]);
+        assert_eq!(streak(&habit, &short, &[], today), 0);
+        assert_eq!(streak(&habit, &short, &["2026-03-15"], today), 0);
+
+        // With five of the seven days neutral, three check-ins can't fit
+        let skipped = ["2026-03-11", "2026-03-12", "2026-03-13", "2026-03-14"];
+        assert_eq!(streak(&habit, &short, &skipped, today), 0);
+        let mut skipped = skipped.to_vec();
+        skipped.push("2026-03-15");
+        assert_eq!(streak(&habit, &short, &skipped, today), 1);
+    }
+
+    #[test]
+    fn times_per_month_follows_calendar_months() {
+        let habit = Habit {
+            target_count: Some(2),
+            ..habit(Frequency::TimesPerMonth, "2026-01-15")
+        };
+        let today = "2026-03-10";
+
+        // February's last day is the 28th; March 1st counts for March
+        let done = [
+            "2026-01-20",
+            "2026-01-31",
+            "2026-02-01",
+            "2026-02-28",
+            "2026-03-01",
+        ];
+        assert_eq!(streak(&habit, &done, &[], today), 2);
+
+        let short_february = ["2026-01-20", "2026-01-31", "2026-02-28", "2026-03-01"];
+        assert_eq!(streak(&habit, &short_february, &[], today), 0);
+        assert_eq!(streak(&habit, &short_february, &["2026-02-14"], today), 0);
+    }
+
+    #[test]
+    fn every_n_days_needs_one_check_in_per_block() {
+        let habit = Habit {
+            interval_days: Some(2),
+            ..habit(Frequency::EveryNDays, "2026-03-01")
+        };
+        // Blocks start on the 1st, 3rd, 5th and 7th; the last is in progress
+        let today = "2026-03-07";
+
+        assert_eq!(
+            streak(
+                &habit,
+                &["2026-03-02", "2026-03-03", "2026-03-06"],
+                &[],
+                today
+            ),
+            3
+        );
+        assert_eq!(streak(&habit, &["2026-03-02", "2026-03-06"], &[], today), 1);
+        let done = ["2026-03-02", "2026-03-06"];
+        // The 3rd was still open, so skipping the 4th doesn't excuse the block
+        assert_eq!(streak(&habit, &done, &["2026-03-04"], today), 1);
+        assert_eq!(
+            streak(&habit, &done, &["2026-03-03", "2026-03-04"], today),
+            2
+        );
+    }
+
+    #[test]
+    fn totals_count_completed_days_up_to_today() {
+        let daily = habit(Frequency::Daily, "2026-03-01");
+        let done = completions(&["2026-03-01", "2026-03-02", "2026-03-09"]);
+        let neutral = NeutralDays::new(&[], &[], &[]);
+        let stats = compute_stats(&daily, Tz::UTC, &done, &neutral, date("2026-03-02"));
+        assert_eq!(stats.total_completions, 2);
+
+        // Measured days only count once they reach the daily target
+        let measured = Habit {
+            unit: Some("pages".to_string()),
+            daily_target: Some(10.0),
+            ..habit(Frequency::Daily, "2026-03-01")
+        };
+        let mut entries = completions(&["2026-03-01", "2026-03-02", "2026-03-09"]);
+        for (entry, value) in entries.iter_mut().zip([12.0, 4.0, 30.0]) {
+            entry.value = Some(value);
+        }
+        let stats = compute_stats(&measured, Tz::UTC, &entries, &neutral, date("2026-03-02"));
+        assert_eq!(stats.total_completions, 1);
+        assert_eq!(stats.total_value, Some(16.0));
+        assert_eq!(stats.average_value, Some(8.0));
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use utoipa_axum::routes;
+
+use crate::{db::MIGRATOR, openapi::ApiRouter, state::AppState};
+
+// A wedged database shouldn't hang the orchestrator's probe
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Health {
+    /// `ok`
+    pub status: &'static str,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Readiness {
+    /// `ready`, `not_ready` or `shutting_down`
+    pub status: &'static str,
+    /// Left out while shutting down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checks: Option<ReadinessChecks>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessChecks {
+    /// `ok`, `unreachable` or `timed out`
+    pub database: String,
+    /// `ok`, `N pending` or `unknown`
+    pub migrations: String,
+}
+
+pub fn health_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(healthz))
+        .routes(routes!(readyz))
+}
+
+// Liveness: the process is up and serving requests
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    security(()),
+    responses((status = OK, description = "The process is up", body = Health))
+)]
+async fn healthz() -> impl IntoResponse {
+    (StatusCode::OK, Json(Health { status: "ok" }))
+}
+
+// Readiness: safe to route traffic here. Fails while shutting down, when the
+// database can't be reached or when migrations are missing.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    security(()),
+    responses(
+        (status = OK, description = "Ready for traffic", body = Readiness),
+        (status = SERVICE_UNAVAILABLE, description = "Shutting down, or a check failed", body = Readiness),
+    )
+)]
+async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    if state.shutdown.is_triggered() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(Readiness {
+                status: "shutting_down",
+                checks: None,
+            }),
+        );
+    }
+
+    let (database, migrations) =
+        match tokio::time::timeout(CHECK_TIMEOUT, pending_migrations(&state.db_pool)).await {
+            Ok(Ok(0)) => ("ok".to_string(), "ok".to_string()),
+            Ok(Ok(pending)) => ("ok".to_string(), format!("{pending} pending")),
+            Ok(Err(err)) => {
+                tracing::warn!(error = %err, "readiness check failed");
+                ("unreachable".to_string(), "unknown".to_string())
+            }
+            Err(_) => ("timed out".to_string(), "unknown".to_string()),
+        };
+
+    let ready = database == "ok" && migrations == "ok";
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(Readiness {
+            status: if ready { "ready" } else { "not_ready" },
+            checks: Some(ReadinessChecks {
+                database,
+                migrations,
+            }),
+        }),
+    )
+}
+
+// Embedded migrations not yet recorded as applied; doubles as the database ping
+async fn pending_migrations(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let applied =
+        sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await;
+
+    let applied = match applied {
+        Ok(applied) => applied,
+        // No migrations table yet: nothing has been applied
+        Err(sqlx::Error::Database(err)) if err.code().as_deref() == Some("42P01") => Vec::new(),
+        Err(err) => return Err(err),
+    };
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration() && !applied.contains(&m.version))
+        .count())
+}
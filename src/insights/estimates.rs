@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use chrono::Datelike;
+use chrono_tz::Tz;
+
+use crate::models::{
+    insight::{EstimateAccuracy, EstimateSummary, MonthlyEstimates},
+    task::TaskWithEffort,
+};
+
+// A task within this fraction of its estimate counts as on target
+pub const ON_TARGET_TOLERANCE: f64 = 0.2;
+
+// Only completed tasks with an estimate are scored; months are the user's
+// calendar months in which the tasks were completed.
+pub fn estimate_accuracy(tasks: &[TaskWithEffort], tz: Tz) -> EstimateAccuracy {
+    let mut overall = Tally::default();
+    let mut months: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut ratios = Vec::new();
+
+    for item in tasks {
+        let (Some(completed_at), Some(estimate), Some(ratio)) = (
+            item.task.completed_at.filter(|_| item.task.completed),
+            item.effort.estimated_minutes,
+            item.effort.estimate_ratio,
+        ) else {
+            continue;
+        };
+
+        let local = completed_at.with_timezone(&tz);
+        let month = format!("{:04}-{:02}", local.year(), local.month());
+        overall.add(estimate, item.effort.actual_minutes);
+        months
+            .entry(month)
+            .or_default()
+            .add(estimate, item.effort.actual_minutes);
+        ratios.push(ratio);
+    }
+
+    ratios.sort_by(f64::total_cmp);
+    let median_ratio = match ratios.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(ratios[n / 2]),
+        n => Some(round((ratios[n / 2 - 1] + ratios[n / 2]) / 2.0)),
+    };
+    let count = |matches: fn(f64) -> bool| ratios.iter().filter(|&&r| matches(r)).count() as i64;
+
+    EstimateAccuracy {
+        overall: overall.summary(),
+        median_ratio,
+        underestimated: count(|r| r > 1.0 + ON_TARGET_TOLERANCE),
+        overestimated: count(|r| r < 1.0 - ON_TARGET_TOLERANCE),
+        on_target: count(|r| (r - 1.0).abs() <= ON_TARGET_TOLERANCE),
+        by_month: months
+            .into_iter()
+            .map(|(month, tally)| MonthlyEstimates {
+                month,
+                summary: tally.summary(),
+            })
+            .collect(),
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    tasks: i64,
+    estimated_minutes: i64,
+    actual_minutes: f64,
+}
+
+impl Tally {
+    fn add(&mut self, estimate: i64, actual: f64) {
+        self.tasks += 1;
+        self.estimated_minutes += estimate;
+        self.actual_minutes += actual;
+    }
+
+    fn summary(&self) -> EstimateSummary {
+        EstimateSummary {
+            tasks: self.tasks,
+            estimated_minutes: self.estimated_minutes,
+            actual_minutes: round(self.actual_minutes),
+            ratio: (self.estimated_minutes > 0)
+                .then(|| round(self.actual_minutes / self.estimated_minutes as f64)),
+        }
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        goal::GoalStatus,
+        insight::{GoalForecast, GoalWithForecast, RiskLevel, TaskRisk},
+    },
+    repo::{GoalRepo, RepoResult, TaskRepo, goals::GoalProgress},
+};
+
+// How many trailing days of completed_at history feed the velocity estimate
+pub const VELOCITY_WINDOW_DAYS: i32 = 28;
+
+// Completions on days after this one count towards velocity
+fn window_start(today: NaiveDate) -> NaiveDate {
+    today - Duration::days(VELOCITY_WINDOW_DAYS.into())
+}
+
+// `today` must already be in the user's timezone `tz`
+pub async fn goals_with_forecast(
+    goals: &dyn GoalRepo,
+    user_id: Uuid,
+    tz: Tz,
+    today: NaiveDate,
+) -> RepoResult<Vec<GoalWithForecast>> {
+    let rows = goals
+        .list_with_progress(user_id, tz, window_start(today))
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let forecast = forecast_goal(&row, tz, today);
+            GoalWithForecast {
+                goal: row.goal,
+                forecast,
+            }
+        })
+        .collect())
+}
+
+fn forecast_goal(row: &GoalProgress, tz: Tz, today: NaiveDate) -> Option<GoalForecast> {
+    if row.goal.status != GoalStatus::Active {
+        return None;
+    }
+
+    // Young goals are measured over their lifetime so far, not the full window
+    let created_on = row.goal.created_at.with_timezone(&tz).date_naive();
+    let age_days = (today - created_on).num_days() + 1;
+    let window_days = age_days.clamp(1, VELOCITY_WINDOW_DAYS as i64);
+    let velocity = row.recent_completions as f64 / window_days as f64;
+
+    let remaining = row.total_tasks - row.completed_tasks;
+    let projected_completion = if row.total_tasks == 0 {
+        None
+    } else if remaining == 0 {
+        Some(today)
+    } else if velocity > 0.0 {
+        let days_needed = (remaining as f64 / velocity).ceil() as i64;
+        Some(today + Duration::days(days_needed))
+    } else {
+        None
+    };
+
+    let risk = match row.goal.deadline {
+        Some(deadline) if deadline < today => RiskLevel::Overdue,
+        Some(deadline) if remaining > 0 => match projected_completion {
+            Some(projected) if projected <= deadline => RiskLevel::OnTrack,
+            _ => RiskLevel::AtRisk,
+        },
+        _ => RiskLevel::OnTrack,
+    };
+
+    let completion_rate = if row.total_tasks == 0 {
+        0.0
+    } else {
+        row.completed_tasks as f64 / row.total_tasks as f64
+    };
+
+    Some(GoalForecast {
+        total_tasks: row.total_tasks,
+        completed_tasks: row.completed_tasks,
+        completion_rate,
+        velocity_per_day: velocity,
+        projected_completion,
+        risk,
+    })
+}
+
+// Open tasks with a due date, flagged as overdue or as part of a pile-up: a day
+// with more tasks due than the user has recently been completing per day.
+pub async fn task_risks(
+    tasks: &dyn TaskRepo,
+    user_id: Uuid,
+    tz: Tz,
+    today: NaiveDate,
+) -> RepoResult<Vec<TaskRisk>> {
+    let open = tasks.open_with_due_date(user_id).await?;
+    let recent_completions = tasks
+        .count_completed_after(user_id, tz, window_start(today))
+        .await?;
+
+    // Everyone can finish at least one task a day, even with no history yet
+    let daily_capacity =
+        ((recent_completions as f64 / VELOCITY_WINDOW_DAYS as f64).ceil() as i64).max(1);
+
+    let mut due_per_day: HashMap<NaiveDate, i64> = HashMap::new();
+    for task in &open {
+        if let Some(due) = task.due_date {
+            *due_per_day.entry(due).or_default() += 1;
+        }
+    }
+
+    Ok(open
+        .into_iter()
+        .filter_map(|task| {
+            let due = task.due_date?;
+            let tasks_due_same_day = due_per_day[&due];
+            let risk = if due < today {
+                RiskLevel::Overdue
+            } else if tasks_due_same_day > daily_capacity {
+                RiskLevel::AtRisk
+            } else {
+                RiskLevel::OnTrack
+            };
+            Some(TaskRisk {
+                task,
+                risk,
+                tasks_due_same_day,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,62 @@
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+
+use crate::{
+    auth::middleware::AuthUser,
+    error::AppError,
+    insights::{
+        estimates::estimate_accuracy,
+        forecast::{goals_with_forecast, task_risks},
+    },
+    models::insight::{AtRiskResponse, EstimatesResponse, RiskLevel},
+    state::AppState,
+    tasks::effort::tasks_with_effort,
+    timezone::{today_in, user_timezone},
+};
+
+#[utoipa::path(
+    get,
+    path = "/at-risk",
+    tag = "insights",
+    responses((status = OK, description = "Goals and tasks that are at_risk or overdue", body = AtRiskResponse))
+)]
+pub async fn get_at_risk(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let tz = user_timezone(&*state.repos.users, auth.user_id).await?;
+    let today = today_in(tz);
+    let goals = goals_with_forecast(&*state.repos.goals, auth.user_id, tz, today).await?;
+    let tasks = task_risks(&*state.repos.tasks, auth.user_id, tz, today).await?;
+
+    let goals: Vec<_> = goals
+        .into_iter()
+        .filter(|g| matches!(&g.forecast, Some(f) if f.risk != RiskLevel::OnTrack))
+        .collect();
+    let tasks: Vec<_> = tasks
+        .into_iter()
+        .filter(|t| t.risk != RiskLevel::OnTrack)
+        .collect();
+
+    Ok((StatusCode::OK, Json(AtRiskResponse { goals, tasks })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/estimates",
+    tag = "insights",
+    responses((status = OK, body = EstimatesResponse))
+)]
+pub async fn get_estimate_accuracy(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let tz = user_timezone(&*state.repos.users, auth.user_id).await?;
+    let tasks = tasks_with_effort(&*state.repos.tasks, auth.user_id, None).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(EstimatesResponse {
+            estimates: estimate_accuracy(&tasks, tz),
+        }),
+    ))
+}
@@ -0,0 +1,4 @@
+pub mod estimates;
+pub mod forecast;
+pub mod handlers;
+pub mod router;
@@ -0,0 +1,10 @@
+use utoipa_axum::routes;
+
+use crate::insights::handlers;
+use crate::openapi::ApiRouter;
+
+pub fn insights_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(handlers::get_at_risk))
+        .routes(routes!(handlers::get_estimate_accuracy))
+}
@@ -0,0 +1,53 @@
+use axum::Router;
+
+use crate::{config::Config, state::AppState};
+
+pub mod auth;
+pub mod commands;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod goals;
+pub mod habits;
+pub mod health;
+pub mod insights;
+pub mod logging;
+pub mod metrics;
+pub mod models;
+pub mod openapi;
+pub mod pomodoro;
+pub mod repo;
+pub mod request_id;
+pub mod shutdown;
+pub mod state;
+pub mod tasks;
+pub mod timezone;
+pub mod vacations;
+pub mod validation;
+pub mod views;
+
+// The whole HTTP application: API, views and docs with the request-id,
+// tracing, metrics and CORS layers. The metrics port, if one is configured,
+// is served separately by the caller.
+pub fn app(config: &Config, state: AppState) -> Router {
+    let (api, spec) = openapi::api();
+    let mut app = api
+        .merge(views::router::views_router())
+        .merge(openapi::docs_router(spec));
+    if config.metrics.enabled {
+        // Installing the recorder up front also turns on the domain counters
+        metrics::handle();
+        if config.metrics_address().is_none() {
+            app = app.merge(metrics::metrics_router());
+        }
+        app = app.layer(axum::middleware::from_fn(metrics::track_requests));
+    }
+    let mut app = app
+        .layer(logging::trace_layer())
+        .layer(axum::middleware::from_fn(request_id::assign_request_id))
+        .with_state(state);
+    if let Some(cors) = config.cors_layer() {
+        app = app.layer(cors);
+    }
+    app
+}
@@ -0,0 +1,84 @@
+use std::{io::IsTerminal, time::Duration};
+
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::{Request, Response},
+};
+use tower_http::{
+    classify::{ServerErrorsAsFailures, SharedClassifier},
+    trace::{DefaultOnBodyChunk, DefaultOnEos, DefaultOnRequest, TraceLayer},
+};
+use tracing::{Span, field};
+use tracing_subscriber::EnvFilter;
+
+use crate::{config::LogConfig, request_id::REQUEST_ID_HEADER};
+
+pub type RequestTraceLayer = TraceLayer<
+    SharedClassifier<ServerErrorsAsFailures>,
+    fn(&Request<Body>) -> Span,
+    DefaultOnRequest,
+    fn(&Response<Body>, Duration, &Span),
+    DefaultOnBodyChunk,
+    DefaultOnEos,
+    (),
+>;
+
+// Installs the global subscriber; sqlx's query events come through it as
+// well, under the `sqlx::query` target. Logs go to stderr so the output of
+// the admin commands stays clean. `config` has been validated by now.
+pub fn init(config: &LogConfig) {
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(&config.level))
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal());
+    if config.format == "json" {
+        builder
+            .json()
+            .flatten_event(true)
+            .with_span_list(false)
+            .init();
+    } else {
+        builder.init();
+    }
+}
+
+// One span per request; everything logged while handling it, including the
+// queries it runs, carries its fields. Failures aren't logged here because
+// AppError already logs the cause of every 5xx.
+pub fn trace_layer() -> RequestTraceLayer {
+    TraceLayer::new_for_http()
+        .make_span_with(request_span as fn(&Request<Body>) -> Span)
+        .on_response(request_finished as fn(&Response<Body>, Duration, &Span))
+        .on_failure(())
+}
+
+// Runs inside assign_request_id, which has put the id on the request.
+// `user_id` is filled in by AuthUser, `status` and `latency_ms` at the end.
+fn request_span(request: &Request<Body>) -> Span {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path(), MatchedPath::as_str);
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        route,
+        request_id,
+        user_id = field::Empty,
+        status = field::Empty,
+        latency_ms = field::Empty,
+    )
+}
+
+fn request_finished(response: &Response<Body>, latency: Duration, span: &Span) {
+    span.record("status", response.status().as_u16());
+    span.record("latency_ms", latency.as_millis() as u64);
+    tracing::info!("finished");
+}
@@ -1,44 +1,170 @@
-use crate::{
-    auth::router::auth_router, goals::router::goals_router, habits::router::habits_router,
-    pomodoro::router::pomodoro_router, tasks::router::tasks_router,
-};
-use axum::Router;
+use clap::Parser;
+use cli::{Command, MigrateAction};
 use dotenv::dotenv;
-use std::env;
+use projekt::{
+    commands::{self, CommandError},
+    config::Config,
+    logging, metrics,
+    pomodoro::{self, events::SessionEvents},
+    repo::Repos,
+    shutdown::{self, Shutdown},
+    state::AppState,
+};
+use std::{future::IntoFuture, process::ExitCode, time::Duration};
 
-mod auth;
-mod goals;
-mod habits;
-mod models;
-mod pomodoro;
-mod state;
-mod tasks;
-mod views;
+mod cli;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
+    let cli = cli::Cli::parse();
     dotenv().ok();
-    let db_url = env::var("DATABASE_URL").expect("DATABBASE_URL must be set.");
-    let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set.");
 
-    let db_pool = sqlx::PgPool::connect(&db_url)
-        .await
-        .expect("falied to connect to db.");
+    let config = match Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // Printed before validating so a broken setup can be inspected too
+    if cli.print_config {
+        print!("{}", config.to_redacted_toml());
+    }
+    let command = cli.command.unwrap_or_default();
+    if let Err(err) = config.validate(matches!(command, Command::Serve { .. })) {
+        eprintln!("error: {err}");
+        return ExitCode::FAILURE;
+    }
+    if cli.print_config {
+        return ExitCode::SUCCESS;
+    }
+    logging::init(&config.log);
+
+    match run(command, config).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(command: Command, config: Config) -> Result<(), CommandError> {
+    let pool = config.connect_db().await.map_err(CommandError::Connect)?;
+
+    match command {
+        Command::Serve { migrate } => {
+            if migrate {
+                commands::migrate_up(&pool).await?;
+            }
+            serve(config, pool).await
+        }
+        Command::Migrate {
+            action: MigrateAction::Up,
+        } => commands::migrate_up(&pool).await,
+        Command::Migrate {
+            action: MigrateAction::Status,
+        } => commands::migrate_status(&pool).await,
+        Command::CreateUser {
+            username,
+            email,
+            timezone,
+        } => commands::create_user(&pool, username, email, timezone).await,
+        Command::ResetPassword { email } => commands::reset_password(&pool, email).await,
+        Command::PurgeDeleted {
+            older_than_days,
+            dry_run,
+        } => commands::purge_deleted(&pool, older_than_days, dry_run).await,
+    }
+}
 
-    let app_state = state::AppState::new(db_pool, jwt_secret);
-    let app = Router::new()
-        .merge(views::router::views_router())
-        .nest("/auth", auth_router())
-        .nest("/tasks", tasks_router())
-        .nest("/goals", goals_router())
-        .nest("/habits", habits_router())
-        .nest("/pomodoro", pomodoro_router())
-        .with_state(app_state);
+// Runs until SIGTERM or Ctrl-C, then drains: /readyz fails, the listener
+// closes after server.shutdown_delay_secs, in-flight requests get
+// server.shutdown_timeout_secs to finish and the pool is closed last
+async fn serve(config: Config, db_pool: sqlx::PgPool) -> Result<(), CommandError> {
+    let shutdown = Shutdown::new();
 
-    let addr: String = "127.0.0.1:3000".to_string();
-    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+    let pg_notify = config.features.pomodoro_pg_notify;
+    let session_events = SessionEvents::new(pg_notify.then(|| db_pool.clone()));
+    if pg_notify {
+        let events = session_events.clone();
+        let pool = db_pool.clone();
+        let repos = Repos::postgres(db_pool.clone());
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            // Dropping the listener hands its connection back before the pool closes
+            tokio::select! {
+                result = events.listen(pool, repos) => if let Err(err) = result {
+                    tracing::error!(error = %err, "pomodoro event listener stopped");
+                },
+                _ = shutdown.triggered() => {}
+            }
+        });
+    }
 
-    println!("Listening on http://{}", &addr);
+    if config.features.pomodoro_reaper {
+        tokio::spawn(pomodoro::reaper::run(
+            db_pool.clone(),
+            session_events.clone(),
+            shutdown.clone(),
+        ));
+    }
 
-    axum::serve(listener, app).await.unwrap();
+    let app_state = AppState::new(
+        db_pool.clone(),
+        config.auth.jwt_secret.clone(),
+        chrono::Duration::hours(config.auth.token_lifetime_hours),
+        session_events,
+        shutdown.clone(),
+    );
+    let app = projekt::app(&config, app_state.clone());
+
+    if let Some((host, port)) = config.metrics_address() {
+        let listener = bind(host, port).await?;
+        tracing::info!("serving metrics on http://{host}:{port}/metrics");
+        let admin = metrics::metrics_router().with_state(app_state);
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let served = axum::serve(listener, admin)
+                .with_graceful_shutdown(async move { shutdown.triggered().await })
+                .await;
+            if let Err(err) = served {
+                tracing::error!(error = %err, "metrics listener stopped");
+            }
+        });
+    }
+
+    let (host, port) = config.bind_address();
+    let listener = bind(host, port).await?;
+    tracing::info!("listening on http://{host}:{port}");
+
+    let delay = Duration::from_secs(config.server.shutdown_delay_secs);
+    let timeout = Duration::from_secs(config.server.shutdown_timeout_secs);
+    let server = axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown::on_signal(shutdown.clone(), delay))
+        .into_future();
+    let deadline = async {
+        shutdown.triggered().await;
+        tokio::time::sleep(delay + timeout).await;
+    };
+    tokio::select! {
+        result = server => result?,
+        _ = deadline => tracing::warn!(
+            "requests still running after {}s, stopping anyway",
+            timeout.as_secs()
+        ),
+    }
+
+    db_pool.close().await;
+    tracing::info!("stopped");
+    Ok(())
+}
+
+async fn bind(host: &str, port: u16) -> Result<tokio::net::TcpListener, CommandError> {
+    tokio::net::TcpListener::bind((host, port))
+        .await
+        .map_err(|source| CommandError::Listen {
+            addr: format!("{host}:{port}"),
+            source,
+        })
 }
@@ -0,0 +1,142 @@
+use std::{sync::OnceLock, time::Instant};
+
+use ::metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
+use axum::{
+    Router,
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use serde::Serialize;
+
+use crate::{
+    models::pomodoro::{SessionStatus, SessionType},
+    state::AppState,
+};
+
+const HTTP_DURATION: &str = "http_request_duration_seconds";
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+// Installs the global recorder on first use. Until then (e.g. with metrics
+// disabled) the counters below are no-ops.
+pub fn handle() -> PrometheusHandle {
+    HANDLE.get_or_init(install).clone()
+}
+
+fn install() -> PrometheusHandle {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Full(HTTP_DURATION.to_string()), &LATENCY_BUCKETS)
+        .and_then(PrometheusBuilder::install_recorder)
+        .expect("no other metrics recorder is installed");
+
+    describe_counter!("http_requests_total", "HTTP requests by route and status");
+    describe_histogram!(HTTP_DURATION, "HTTP request latency by route and status");
+    describe_gauge!(
+        "db_pool_connections",
+        "Open database connections by state (idle or in_use)"
+    );
+    describe_gauge!("db_pool_max_connections", "Size limit of the database pool");
+    describe_counter!("tasks_created_total", "Tasks created");
+    describe_counter!("tasks_completed_total", "Tasks marked completed");
+    describe_counter!(
+        "habit_checkins_total",
+        "Habit check-ins, live (check_in) or backfilled"
+    );
+    describe_counter!(
+        "pomodoro_sessions_started_total",
+        "Pomodoro sessions started by session type"
+    );
+    describe_counter!(
+        "pomodoro_sessions_ended_total",
+        "Pomodoro sessions ended by outcome, including auto-closed ones"
+    );
+    describe_counter!("logins_total", "Login attempts by result");
+
+    handle
+}
+
+pub fn metrics_router() -> Router<AppState> {
+    Router::new().route("/metrics", get(render))
+}
+
+// Pool gauges are sampled at scrape time rather than on every checkout
+async fn render(State(state): State<AppState>) -> impl IntoResponse {
+    let pool = &state.db_pool;
+    let idle = pool.num_idle() as f64;
+    gauge!("db_pool_connections", "state" => "idle").set(idle);
+    gauge!("db_pool_connections", "state" => "in_use").set(f64::from(pool.size()) - idle);
+    gauge!("db_pool_max_connections").set(f64::from(pool.options().get_max_connections()));
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        handle().render(),
+    )
+}
+
+// Labels requests by route template so /tasks/{id} is one series, not one
+// per task; anything that matched no route is lumped together
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+
+    let response = next.run(request).await;
+
+    let labels = [
+        ("method", method),
+        ("route", route),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    counter!("http_requests_total", &labels).increment(1);
+    histogram!(HTTP_DURATION, &labels).record(started.elapsed().as_secs_f64());
+    response
+}
+
+pub fn task_created() {
+    counter!("tasks_created_total").increment(1);
+}
+
+pub fn task_completed() {
+    counter!("tasks_completed_total").increment(1);
+}
+
+pub fn habit_checked_in(source: &'static str, count: u64) {
+    counter!("habit_checkins_total", "source" => source).increment(count);
+}
+
+pub fn pomodoro_started(session_type: &SessionType) {
+    counter!("pomodoro_sessions_started_total", "type" => label(session_type)).increment(1);
+}
+
+pub fn pomodoro_ended(status: SessionStatus, auto_closed: bool) {
+    counter!(
+        "pomodoro_sessions_ended_total",
+        "status" => label(&status),
+        "auto_closed" => auto_closed.to_string(),
+    )
+    .increment(1);
+}
+
+pub fn login(succeeded: bool) {
+    let result = if succeeded { "success" } else { "failure" };
+    counter!("logins_total", "result" => result).increment(1);
+}
+
+// The enum's wire name, e.g. "short_break"
+fn label(value: &impl Serialize) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
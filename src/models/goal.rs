@@ -1,9 +1,14 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone)]
-#[sqlx(type_name = "text", rename_all = "snake_case")]
+use crate::models::insight::GoalWithForecast;
+use crate::validation::{MAX_TEXT_LEN, MAX_TITLE_LEN, not_blank, not_in_past};
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone, ToSchema)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum GoalStatus {
     Active,
@@ -11,7 +16,7 @@ pub enum GoalStatus {
     Abandoned,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
 pub struct Goal {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -22,17 +27,33 @@ pub struct Goal {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateGoal {
+    #[validate(length(max = MAX_TITLE_LEN), custom(function = not_blank))]
     pub title: String,
+    #[validate(length(max = MAX_TEXT_LEN))]
     pub description: Option<String>,
+    #[validate(custom(function = not_in_past))]
     pub deadline: Option<NaiveDate>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateGoal {
+    #[validate(length(max = MAX_TITLE_LEN), custom(function = not_blank))]
     pub title: Option<String>,
+    #[validate(length(max = MAX_TEXT_LEN))]
     pub description: Option<String>,
+    #[validate(custom(function = not_in_past))]
     pub deadline: Option<NaiveDate>,
     pub status: Option<GoalStatus>,
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GoalResponse {
+    pub goal: Goal,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GoalsResponse {
+    pub goals: Vec<GoalWithForecast>,
+}
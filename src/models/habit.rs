@@ -1,44 +1,227 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone)]
-#[sqlx(type_name = "text", rename_all = "snake_case")]
+use crate::validation::{MAX_NOTE_LEN, MAX_TITLE_LEN, not_blank};
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone, ToSchema)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum Frequency {
     Daily,
     Weekly,
+    Weekdays,
+    TimesPerWeek,
+    TimesPerMonth,
+    EveryNDays,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
 pub struct Habit {
     pub id: Uuid,
     pub user_id: Uuid,
     pub name: String,
     pub frequency: Frequency,
     pub created_at: DateTime<Utc>,
+    pub weekdays: Option<Vec<i16>>,
+    pub target_count: Option<i32>,
+    pub interval_days: Option<i32>,
+    pub unit: Option<String>,
+    pub daily_target: Option<f64>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub paused_until: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone, ToSchema)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SkipKind {
+    Skip,
+    Freeze,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
+pub struct HabitSkip {
+    pub id: Uuid,
+    pub habit_id: Uuid,
+    pub skipped_on: NaiveDate,
+    pub kind: SkipKind,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
 pub struct HabitCompletion {
     pub id: Uuid,
     pub habit_id: Uuid,
     pub completed_on: NaiveDate,
+    pub value: Option<f64>,
+    /// Checked in on the day itself rather than backdated; only these earn freezes
+    pub same_day: bool,
+}
+
+// An inclusive range of days the habit was paused
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct HabitPause {
+    pub id: Uuid,
+    pub habit_id: Uuid,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateHabit {
+    #[validate(length(max = MAX_TITLE_LEN), custom(function = not_blank))]
     pub name: String,
     pub frequency: Option<Frequency>,
+    pub weekdays: Option<Vec<i16>>,
+    pub target_count: Option<i32>,
+    pub interval_days: Option<i32>,
+    #[validate(length(max = 32))]
+    pub unit: Option<String>,
+    #[validate(range(exclusive_min = 0.0))]
+    pub daily_target: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateHabit {
+    #[validate(length(max = MAX_TITLE_LEN), custom(function = not_blank))]
     pub name: Option<String>,
     pub frequency: Option<Frequency>,
+    pub weekdays: Option<Vec<i16>>,
+    pub target_count: Option<i32>,
+    pub interval_days: Option<i32>,
+    #[validate(length(max = 32))]
+    pub unit: Option<String>,
+    #[validate(range(exclusive_min = 0.0))]
+    pub daily_target: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CompleteHabit {
     pub completed_on: Option<NaiveDate>,
+    /// Required for measured habits; repeated entries on the same day are summed
+    #[validate(range(exclusive_min = 0.0))]
+    pub value: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SkipHabit {
+    pub skipped_on: Option<NaiveDate>,
+    #[validate(length(max = MAX_NOTE_LEN))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct FreezeHabit {
+    pub frozen_on: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HabitFilter {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct PauseHabit {
+    /// Last day of the pause; the habit is due again the day after
+    pub until: NaiveDate,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct BackfillHabit {
+    #[validate(length(min = 1, max = 366, message = "must list between 1 and 366 dates"))]
+    pub dates: Vec<NaiveDate>,
+}
+
+/// Streaks and rates are counted in scheduled periods: days for daily and weekday
+/// habits, ISO weeks for weekly and times_per_week, calendar months for
+/// times_per_month and N-day blocks for every_n_days
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HabitStats {
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub completion_rate_7d: f64,
+    pub completion_rate_30d: f64,
+    pub completion_rate_365d: f64,
+    /// Completed days up to today; measured habits only count days that reached the daily_target
+    pub total_completions: i64,
+    /// Only set for measured habits (those with a daily_target)
+    pub total_value: Option<f64>,
+    pub average_value: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HabitWithStats {
+    #[serde(flatten)]
+    pub habit: Habit,
+    pub stats: HabitStats,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HabitsResponse {
+    pub habits: Vec<HabitWithStats>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DueHabitsResponse {
+    pub habits: Vec<Habit>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HabitResponse {
+    pub habit: Habit,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HabitStatsResponse {
+    pub stats: HabitStats,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CompletionResponse {
+    pub completion: HabitCompletion,
+    /// Whether this check-in earned the user a streak freeze
+    pub freeze_earned: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CompletionsResponse {
+    pub completions: Vec<HabitCompletion>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackfillResponse {
+    pub inserted: Vec<NaiveDate>,
+    /// Days of a measured habit that had a partial measurement, now raised to the daily_target
+    pub topped_up: Vec<NaiveDate>,
+    pub already_present: Vec<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SkipsResponse {
+    pub skips: Vec<HabitSkip>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SkipResponse {
+    pub skip: HabitSkip,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FreezeResponse {
+    pub skip: HabitSkip,
+    /// Freezes the user has left
+    pub streak_freezes: i32,
 }
@@ -0,0 +1,77 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::{goal::Goal, task::Task};
+
+#[derive(Debug, Serialize, PartialEq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    OnTrack,
+    AtRisk,
+    Overdue,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GoalForecast {
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub completion_rate: f64,
+    pub velocity_per_day: f64,
+    pub projected_completion: Option<NaiveDate>,
+    pub risk: RiskLevel,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GoalWithForecast {
+    #[serde(flatten)]
+    pub goal: Goal,
+    /// Only active goals are forecast; completed and abandoned ones carry None
+    pub forecast: Option<GoalForecast>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskRisk {
+    #[serde(flatten)]
+    pub task: Task,
+    pub risk: RiskLevel,
+    pub tasks_due_same_day: i64,
+}
+
+#[derive(Debug, Serialize, Default, ToSchema)]
+pub struct EstimateSummary {
+    pub tasks: i64,
+    pub estimated_minutes: i64,
+    pub actual_minutes: f64,
+    /// Total actual over total estimated
+    pub ratio: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MonthlyEstimates {
+    pub month: String,
+    #[serde(flatten)]
+    pub summary: EstimateSummary,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EstimateAccuracy {
+    #[serde(flatten)]
+    pub overall: EstimateSummary,
+    pub median_ratio: Option<f64>,
+    pub underestimated: i64,
+    pub overestimated: i64,
+    pub on_target: i64,
+    pub by_month: Vec<MonthlyEstimates>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AtRiskResponse {
+    pub goals: Vec<GoalWithForecast>,
+    pub tasks: Vec<TaskRisk>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EstimatesResponse {
+    pub estimates: EstimateAccuracy,
+}
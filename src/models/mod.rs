@@ -1,5 +1,7 @@
 pub mod goal;
 pub mod habit;
+pub mod insight;
 pub mod pomodoro;
 pub mod task;
 pub mod user;
+pub mod vacation;
@@ -1,9 +1,13 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone)]
-#[sqlx(type_name = "text", rename_all = "snake_case")]
+use crate::validation::MAX_NOTE_LEN;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone, ToSchema)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum SessionType {
     Focus,
@@ -11,7 +15,17 @@ pub enum SessionType {
     LongBreak,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone, Copy, ToSchema)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Running,
+    Paused,
+    Completed,
+    Interrupted,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
 pub struct PomodoroSession {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -21,16 +35,217 @@ pub struct PomodoroSession {
     pub started_at: DateTime<Utc>,
     pub ended_at: Option<DateTime<Utc>>,
     pub notes: Option<String>,
+    pub status: SessionStatus,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub paused_seconds: i32,
+    pub auto_closed: bool,
 }
 
-#[derive(Debug, Deserialize)]
+/// A session as returned by the API, with its timer worked out server-side
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionView {
+    #[serde(flatten)]
+    pub session: PomodoroSession,
+    pub elapsed_seconds: i64,
+    pub remaining_seconds: i64,
+    /// Only filled in when a single session is fetched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interruptions: Option<Vec<Interruption>>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct StartSession {
     pub task_id: Option<Uuid>,
     pub session_type: Option<SessionType>,
+    #[validate(range(min = 1, max = 240))]
     pub duration_minutes: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StartOptions {
+    /// Interrupt the running session instead of refusing to start a new one
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct EndSession {
+    #[validate(length(max = MAX_NOTE_LEN))]
     pub notes: Option<String>,
 }
+
+/// Internal: the user's own distraction; external: someone or something else
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone, Copy, ToSchema)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptionKind {
+    Internal,
+    External,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
+pub struct Interruption {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub kind: InterruptionKind,
+    pub occurred_at: DateTime<Utc>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct LogInterruption {
+    pub kind: InterruptionKind,
+    pub occurred_at: Option<DateTime<Utc>>,
+    #[validate(length(max = MAX_NOTE_LEN))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct NextSession {
+    pub task_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
+pub struct PomodoroSettings {
+    pub user_id: Uuid,
+    pub focus_minutes: i32,
+    pub short_break_minutes: i32,
+    pub long_break_minutes: i32,
+    pub long_break_every: i32,
+    pub auto_start_next: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdatePomodoroSettings {
+    #[validate(range(min = 1, max = 240))]
+    pub focus_minutes: Option<i32>,
+    #[validate(range(min = 1, max = 240))]
+    pub short_break_minutes: Option<i32>,
+    #[validate(range(min = 1, max = 240))]
+    pub long_break_minutes: Option<i32>,
+    #[validate(range(min = 1, max = 12))]
+    pub long_break_every: Option<i32>,
+    pub auto_start_next: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsGrouping {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Task,
+    Tag,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub group_by: StatsGrouping,
+}
+
+// A finished focus session with the task it was spent on
+#[derive(Debug, sqlx::FromRow)]
+pub struct FocusSession {
+    #[sqlx(flatten)]
+    pub session: PomodoroSession,
+    pub task_title: Option<String>,
+    pub task_tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Default, ToSchema)]
+pub struct FocusTotals {
+    pub focus_minutes: f64,
+    pub sessions: i64,
+    pub completed: i64,
+    pub interrupted: i64,
+    pub interruptions: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FocusGroup {
+    /// Date, week start, "YYYY-MM", task id or tag; null for sessions without a task or tag
+    pub key: Option<String>,
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub totals: FocusTotals,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PomodoroStats {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: StatsGrouping,
+    #[serde(flatten)]
+    pub totals: FocusTotals,
+    pub average_session_minutes: f64,
+    /// Focus minutes by the local hour the session started in, 0-23
+    pub hour_histogram: Vec<f64>,
+    pub best_hour: Option<u32>,
+    pub interruptions: InterruptionStats,
+    pub groups: Vec<FocusGroup>,
+    pub by_task: Vec<FocusGroup>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InterruptionStats {
+    pub internal: i64,
+    pub external: i64,
+    pub top_reasons: Vec<InterruptionReason>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InterruptionReason {
+    pub reason: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionsResponse {
+    pub sessions: Vec<SessionView>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    pub session: SessionView,
+}
+
+/// `session` is null when nothing is running or paused
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CurrentSessionResponse {
+    pub session: Option<SessionView>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EndSessionResponse {
+    pub session: SessionView,
+    /// The session started automatically when auto_start_next is on
+    pub next: Option<SessionView>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InterruptionsResponse {
+    pub interruptions: Vec<Interruption>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InterruptionResponse {
+    pub interruption: Interruption,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettingsResponse {
+    pub settings: PomodoroSettings,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatsResponse {
+    pub stats: PomodoroStats,
+}
@@ -1,9 +1,15 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone)]
-#[sqlx(type_name = "text", rename_all = "snake_case")]
+use crate::validation::{
+    MAX_NOTE_LEN, MAX_TEXT_LEN, MAX_TITLE_LEN, not_blank, not_in_past, nullable, valid_tags,
+};
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Clone, ToSchema)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum Priority {
     Low,
@@ -11,7 +17,7 @@ pub enum Priority {
     High,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
 pub struct Task {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -22,21 +28,115 @@ pub struct Task {
     pub completed: bool,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub goal_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub estimated_pomodoros: Option<i32>,
+    pub estimated_minutes: Option<i32>,
+    /// The pomodoro length `estimated_pomodoros` was made with
+    pub pomodoro_minutes: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateTask {
+    #[validate(length(max = MAX_TITLE_LEN), custom(function = not_blank))]
     pub title: String,
+    #[validate(length(max = MAX_TEXT_LEN))]
     pub notes: Option<String>,
     pub priority: Option<Priority>,
+    #[validate(custom(function = not_in_past))]
     pub due_date: Option<NaiveDate>,
+    pub goal_id: Option<Uuid>,
+    #[validate(custom(function = valid_tags))]
+    pub tags: Option<Vec<String>>,
+    #[validate(range(min = 1, max = 100))]
+    pub estimated_pomodoros: Option<i32>,
+    #[validate(range(min = 1, max = 10_000))]
+    pub estimated_minutes: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateTask {
+    #[validate(length(max = MAX_TITLE_LEN), custom(function = not_blank))]
     pub title: Option<String>,
+    #[validate(length(max = MAX_TEXT_LEN))]
     pub notes: Option<String>,
     pub priority: Option<Priority>,
+    #[validate(custom(function = not_in_past))]
     pub due_date: Option<NaiveDate>,
     pub completed: Option<bool>,
+    /// `null` unlinks the task from its goal
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<Uuid>, nullable)]
+    pub goal_id: Option<Option<Uuid>>,
+    #[validate(custom(function = valid_tags))]
+    pub tags: Option<Vec<String>>,
+    #[validate(range(min = 1, max = 100))]
+    pub estimated_pomodoros: Option<i32>,
+    #[validate(range(min = 1, max = 10_000))]
+    pub estimated_minutes: Option<i32>,
+}
+
+/// Estimated vs. actual effort; actual time is active focus time from linked
+/// pomodoro sessions plus manual time entries
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskEffort {
+    pub estimated_minutes: Option<i64>,
+    pub focus_minutes: f64,
+    pub manual_minutes: i64,
+    pub actual_minutes: f64,
+    pub completed_pomodoros: i64,
+    /// actual / estimated; above 1 means the task took longer than estimated
+    pub estimate_ratio: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskWithEffort {
+    #[serde(flatten)]
+    pub task: Task,
+    pub effort: TaskEffort,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
+pub struct TimeEntry {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub minutes: i32,
+    pub spent_on: NaiveDate,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateTimeEntry {
+    /// At most a full day per entry
+    #[validate(range(min = 1, max = 1440))]
+    pub minutes: i32,
+    pub spent_on: Option<NaiveDate>,
+    #[validate(length(max = MAX_NOTE_LEN))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TasksResponse {
+    pub tasks: Vec<Task>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskResponse {
+    pub task: Task,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskDetailResponse {
+    pub task: TaskWithEffort,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeEntriesResponse {
+    pub time_entries: Vec<TimeEntry>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeEntryResponse {
+    pub time_entry: TimeEntry,
 }
@@ -1,8 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+use crate::validation::not_blank;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
 pub struct User {
     pub id: Uuid,
     pub username: String,
@@ -10,17 +14,41 @@ pub struct User {
     #[serde(skip_serializing)]
     pub password_hash: String,
     pub created_at: DateTime<Utc>,
+    pub timezone: String,
+    pub streak_freezes: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateUser {
+    #[validate(length(max = 50), custom(function = not_blank))]
     pub username: String,
+    #[validate(email, length(max = 255))]
     pub email: String,
+    #[validate(length(min = 8, max = 128))]
     pub password: String,
+    #[validate(length(max = 64))]
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateUser {
+    #[validate(length(max = 64))]
+    pub timezone: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LoginUser {
     pub email: String,
     pub password: String,
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserResponse {
+    pub user: User,
+}
+
+/// A bearer token for the Authorization header
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub token: String,
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::validation::MAX_NOTE_LEN;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
+pub struct Vacation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateVacation {
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    #[validate(length(max = MAX_NOTE_LEN))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VacationsResponse {
+    pub vacations: Vec<Vacation>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VacationResponse {
+    pub vacation: Vacation,
+}
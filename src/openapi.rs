@@ -0,0 +1,286 @@
+use askama::Template;
+use axum::{
+    Json, Router,
+    http::StatusCode,
+    response::{Html, IntoResponse},
+    routing::get,
+};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        self, ContentBuilder, Ref, ResponseBuilder,
+        path::Operation,
+        security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme},
+    },
+};
+use utoipa_axum::router::{OpenApiRouter, UtoipaMethodRouter};
+
+use crate::{
+    auth::auth_router,
+    error::{FieldError, Problem},
+    goals::router::goals_router,
+    habits::router::habits_router,
+    health::health_router,
+    insights::router::insights_router,
+    pomodoro::router::pomodoro_router,
+    state::AppState,
+    tasks::router::tasks_router,
+    vacations::router::vacations_router,
+};
+
+// Must match the name used in the #[openapi(security)] attribute below
+pub const SECURITY_SCHEME: &str = "bearer";
+const PROBLEM_JSON: &str = "application/problem+json";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Projekt API",
+        description = "Tasks, goals, habits and pomodoro sessions. Errors are \
+                       application/problem+json documents; match on their `code`."
+    ),
+    security(("bearer" = [])),
+    modifiers(&BearerAuth),
+    components(schemas(Problem, FieldError)),
+    tags(
+        (name = "auth", description = "Accounts and tokens"),
+        (name = "tasks", description = "Tasks, their effort estimates and time entries"),
+        (name = "goals", description = "Goals and their forecasts"),
+        (name = "habits", description = "Habits, check-ins, skips and streak freezes"),
+        (name = "pomodoro", description = "Pomodoro sessions, settings and stats"),
+        (name = "vacations", description = "Date ranges that pause every habit"),
+        (name = "insights", description = "Deadline risk and estimate accuracy"),
+        (name = "health", description = "Probes for orchestrators"),
+    )
+)]
+struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            SECURITY_SCHEME,
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("The token from POST /auth/login"))
+                    .build(),
+            ),
+        );
+    }
+}
+
+#[derive(Template)]
+#[template(path = "docs.html")]
+struct DocsTemplate;
+
+// An OpenApiRouter that only takes `routes!`, which reads each route's method
+// and path from its #[utoipa::path]. Without OpenApiRouter::route and friends
+// an API route can't exist without being in the document.
+#[derive(Default)]
+pub struct ApiRouter(OpenApiRouter<AppState>);
+
+impl ApiRouter {
+    pub fn new() -> Self {
+        Self(OpenApiRouter::new())
+    }
+
+    pub fn routes(self, routes: UtoipaMethodRouter<AppState>) -> Self {
+        Self(self.0.routes(routes))
+    }
+}
+
+// Every documented route, and only those
+pub fn api() -> (Router<AppState>, openapi::OpenApi) {
+    let (router, mut spec) = OpenApiRouter::with_openapi(ApiDoc::openapi())
+        .merge(health_router().0)
+        .nest("/auth", auth_router().0)
+        .nest("/tasks", tasks_router().0)
+        .nest("/goals", goals_router().0)
+        .nest("/habits", habits_router().0)
+        .nest("/pomodoro", pomodoro_router().0)
+        .nest("/insights", insights_router().0)
+        .nest("/vacations", vacations_router().0)
+        .split_for_parts();
+    add_problem_responses(&mut spec);
+    (router, spec)
+}
+
+// GET /openapi.json and the Swagger UI at GET /docs (loaded from a CDN)
+pub fn docs_router(spec: openapi::OpenApi) -> Router<AppState> {
+    let spec = Json(spec);
+    Router::new()
+        .route("/openapi.json", get(move || async move { spec }))
+        .route("/docs", get(docs_page))
+}
+
+async fn docs_page() -> impl IntoResponse {
+    match DocsTemplate.render() {
+        Ok(html) => Html(html).into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to render API docs",
+        )
+            .into_response(),
+    }
+}
+
+// The errors most operations share, added here rather than repeated on every
+// handler: 401 without a valid token, 400/422 for bad bodies and 404 for
+// unknown path ids. Handler-specific conflicts are documented on the handler.
+fn add_problem_responses(spec: &mut openapi::OpenApi) {
+    for (path, item) in spec.paths.paths.iter_mut() {
+        let operations = [
+            &mut item.get,
+            &mut item.put,
+            &mut item.post,
+            &mut item.delete,
+            &mut item.patch,
+        ];
+        for operation in operations.into_iter().flatten() {
+            if requires_auth(operation) {
+                add_problem(operation, "401", "Missing, invalid or expired token");
+            }
+            if operation.request_body.is_some() {
+                add_problem(operation, "400", "Malformed JSON");
+                add_problem(operation, "422", "The body failed validation");
+            }
+            if path.contains('{') {
+                add_problem(
+                    operation,
+                    "404",
+                    "No such resource, or it belongs to someone else",
+                );
+            }
+        }
+    }
+}
+
+// Operations inherit the document-wide bearer requirement unless they
+// override it with an empty one
+pub fn requires_auth(operation: &Operation) -> bool {
+    match &operation.security {
+        None => true,
+        Some(requirements) => requirements
+            .iter()
+            .any(|requirement| *requirement != SecurityRequirement::default()),
+    }
+}
+
+fn add_problem(operation: &mut Operation, status: &str, description: &str) {
+    operation
+        .responses
+        .responses
+        .entry(status.to_string())
+        .or_insert_with(|| {
+            ResponseBuilder::new()
+                .description(description)
+                .content(
+                    PROBLEM_JSON,
+                    ContentBuilder::new()
+                        .schema(Some(Ref::from_schema_name("Problem")))
+                        .build(),
+                )
+                .build()
+                .into()
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Method, Request},
+    };
+    use sqlx::postgres::PgPoolOptions;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{pomodoro::events::SessionEvents, shutdown::Shutdown};
+
+    // Sends every method on every documented path through the real router
+    // without a token. A documented route missing from the router comes back
+    // as a bare 404 or a 405, and a routed method missing from the document
+    // as anything but a 405; a wrong security requirement shows up as a 401
+    // mismatch. Undocumented paths can't be routed at all, see ApiRouter.
+    // Nothing here reaches the database, so the pool never connects.
+    #[tokio::test]
+    async fn spec_matches_routes() {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(200))
+            .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+            .unwrap();
+        let state = AppState::new(
+            pool,
+            "test-secret".to_string(),
+            chrono::Duration::hours(1),
+            SessionEvents::new(None),
+            Shutdown::new(),
+        );
+        let (router, spec) = api();
+        let app = router.with_state(state);
+
+        let mut checked = 0;
+        for (path, item) in &spec.paths.paths {
+            let operations = [
+                (Method::GET, &item.get),
+                (Method::PUT, &item.put),
+                (Method::POST, &item.post),
+                (Method::DELETE, &item.delete),
+                (Method::PATCH, &item.patch),
+            ];
+            let uri = path
+                .replace("{date}", "2026-01-01")
+                .replace("{id}", &uuid::Uuid::nil().to_string())
+                .replace("{entry_id}", &uuid::Uuid::nil().to_string());
+            for (method, operation) in operations {
+                let request = Request::builder()
+                    .method(method.clone())
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap();
+                let response = app.clone().oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+
+                let Some(operation) = operation else {
+                    assert_eq!(
+                        status,
+                        StatusCode::METHOD_NOT_ALLOWED,
+                        "{method} {path} is routed but not documented"
+                    );
+                    continue;
+                };
+                assert_ne!(
+                    status,
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    "{method} {path} is documented but not routed"
+                );
+                assert!(
+                    !(status == StatusCode::NOT_FOUND && body.is_empty()),
+                    "{method} {path} is documented but not routed"
+                );
+                if requires_auth(operation) {
+                    assert_eq!(
+                        status,
+                        StatusCode::UNAUTHORIZED,
+                        "{method} {path} is documented as authenticated"
+                    );
+                } else {
+                    assert_ne!(
+                        status,
+                        StatusCode::UNAUTHORIZED,
+                        "{method} {path} is documented as public"
+                    );
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked > 50, "only {checked} operations documented");
+    }
+}
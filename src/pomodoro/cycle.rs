@@ -0,0 +1,87 @@
+use crate::models::pomodoro::{PomodoroSettings, SessionType};
+
+// A break follows each focus session, every `long_break_every`-th one a long
+// break; anything else is followed by focus.
+pub fn next_session_type(
+    completed_today: &[SessionType],
+    settings: &PomodoroSettings,
+) -> SessionType {
+    match completed_today.last() {
+        Some(SessionType::Focus) => {
+            let focus_sessions = completed_today
+                .iter()
+                .filter(|t| **t == SessionType::Focus)
+                .count();
+            if focus_sessions % settings.long_break_every.max(1) as usize == 0 {
+                SessionType::LongBreak
+            } else {
+                SessionType::ShortBreak
+            }
+        }
+        _ => SessionType::Focus,
+    }
+}
+
+pub fn duration_for(session_type: &SessionType, settings: &PomodoroSettings) -> i32 {
+    match session_type {
+        SessionType::Focus => settings.focus_minutes,
+        SessionType::ShortBreak => settings.short_break_minutes,
+        SessionType::LongBreak => settings.long_break_minutes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+    use SessionType::{Focus, LongBreak, ShortBreak};
+
+    fn settings(long_break_every: i32) -> PomodoroSettings {
+        PomodoroSettings {
+            user_id: Uuid::nil(),
+            focus_minutes: 50,
+            short_break_minutes: 10,
+            long_break_minutes: 30,
+            long_break_every,
+            auto_start_next: false,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn every_nth_focus_session_earns_a_long_break() {
+        let settings = settings(3);
+        assert_eq!(next_session_type(&[], &settings), Focus);
+        assert_eq!(next_session_type(&[Focus], &settings), ShortBreak);
+        assert_eq!(next_session_type(&[Focus, ShortBreak], &settings), Focus);
+
+        let third = [Focus, ShortBreak, Focus, ShortBreak, Focus];
+        assert_eq!(next_session_type(&third, &settings), LongBreak);
+        assert_eq!(next_session_type(&[Focus, LongBreak], &settings), Focus);
+        // Skipped breaks don't reset the count
+        assert_eq!(
+            next_session_type(&[Focus, Focus, Focus], &settings),
+            LongBreak
+        );
+        assert_eq!(
+            next_session_type(&[Focus, Focus, Focus, Focus], &settings),
+            ShortBreak
+        );
+    }
+
+    #[test]
+    fn a_cycle_of_zero_means_every_break_is_long() {
+        assert_eq!(next_session_type(&[Focus], &settings(0)), LongBreak);
+        assert_eq!(next_session_type(&[Focus], &settings(1)), LongBreak);
+    }
+
+    #[test]
+    fn durations_come_from_the_settings() {
+        let settings = settings(4);
+        assert_eq!(duration_for(&Focus, &settings), 50);
+        assert_eq!(duration_for(&ShortBreak, &settings), 10);
+        assert_eq!(duration_for(&LongBreak, &settings), 30);
+    }
+}
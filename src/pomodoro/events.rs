@@ -0,0 +1,190 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::response::sse::Event;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{PgPool, postgres::PgListener};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use crate::{
+    models::pomodoro::PomodoroSession, pomodoro::timer::view, repo::Repos, shutdown::Shutdown,
+};
+
+const CHANNEL: &str = "pomodoro_events";
+
+// Clients count down locally; ticks just keep devices from drifting apart
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEventKind {
+    Started,
+    Paused,
+    Resumed,
+    Ended,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionEvent {
+    pub kind: SessionEventKind,
+    pub session: PomodoroSession,
+}
+
+// What goes over NOTIFY; payloads are size-limited, so receivers re-read the row
+#[derive(Debug, Serialize, Deserialize)]
+struct Notification {
+    kind: SessionEventKind,
+    user_id: Uuid,
+    session_id: Uuid,
+}
+
+// Fans session changes out to every open /pomodoro/stream connection. With a
+// pool attached, events go through Postgres NOTIFY so that every server
+// instance (this one included) hears them via `listen`; otherwise they are
+// broadcast in-process only.
+#[derive(Clone)]
+pub struct SessionEvents {
+    sender: broadcast::Sender<SessionEvent>,
+    notify_pool: Option<PgPool>,
+}
+
+impl SessionEvents {
+    pub fn new(notify_pool: Option<PgPool>) -> Self {
+        let (sender, _) = broadcast::channel(256);
+        Self {
+            sender,
+            notify_pool,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SessionEvent> {
+        self.sender.subscribe()
+    }
+
+    // Best effort: a lost event only delays clients until their next reconnect
+    pub async fn publish(&self, kind: SessionEventKind, session: &PomodoroSession) {
+        let Some(pool) = &self.notify_pool else {
+            let _ = self.sender.send(SessionEvent {
+                kind,
+                session: session.clone(),
+            });
+            return;
+        };
+
+        let payload = Notification {
+            kind,
+            user_id: session.user_id,
+            session_id: session.id,
+        };
+        let sent = sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(CHANNEL)
+            .bind(serde_json::to_string(&payload).unwrap_or_default())
+            .execute(pool)
+            .await;
+        if let Err(err) = sent {
+            tracing::error!(error = %err, "failed to publish pomodoro event");
+        }
+    }
+
+    // Relays NOTIFY payloads from any instance into the local broadcast.
+    // PgListener reconnects on its own after a dropped connection.
+    pub async fn listen(self, pool: PgPool, repos: Repos) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(&pool).await?;
+        listener.listen(CHANNEL).await?;
+
+        loop {
+            let notification = listener.recv().await?;
+            let Ok(payload) = serde_json::from_str::<Notification>(notification.payload()) else {
+                continue;
+            };
+            let session = repos
+                .pomodoro
+                .find(payload.user_id, payload.session_id)
+                .await;
+            if let Ok(Some(session)) = session {
+                let _ = self.sender.send(SessionEvent {
+                    kind: payload.kind,
+                    session,
+                });
+            }
+        }
+    }
+}
+
+// One SSE connection: a snapshot of the open session, then this user's events
+// and a tick with the open session's remaining time every TICK_INTERVAL. Ends
+// on shutdown so the connection doesn't hold up draining; clients reconnect.
+pub fn client_stream(
+    events: &SessionEvents,
+    repos: Repos,
+    user_id: Uuid,
+    mut current: Option<PomodoroSession>,
+    shutdown: Shutdown,
+) -> ReceiverStream<Result<Event, Infallible>> {
+    let (tx, rx) = mpsc::channel(16);
+    let mut received = events.subscribe();
+
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(TICK_INTERVAL);
+        // The first tick fires immediately and doubles as the snapshot
+        let mut name = "snapshot";
+
+        loop {
+            tokio::select! {
+                _ = shutdown.triggered() => break,
+                _ = ticks.tick() => {}
+                event = received.recv() => match event {
+                    Ok(event) if event.session.user_id == user_id => {
+                        let open = event.session.ended_at.is_none();
+                        let was_current = current.as_ref().is_some_and(|s| s.id == event.session.id);
+                        if open {
+                            current = Some(event.session.clone());
+                        } else if was_current {
+                            current = None;
+                        }
+                        let data = json!({"session": view(event.session, Utc::now())});
+                        let sse = Event::default().event(kind_name(event.kind)).json_data(data);
+                        if tx.send(Ok(sse.unwrap_or_default())).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                    Ok(_) => continue,
+                    // Missed events: re-read the open session and resend a snapshot
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        let reloaded = repos.pomodoro.active(user_id).await;
+                        if let Ok(session) = reloaded {
+                            current = session;
+                        }
+                        name = "snapshot";
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            }
+
+            let session = current.clone().map(|s| view(s, Utc::now()));
+            let sse = Event::default()
+                .event(name)
+                .json_data(json!({"session": session}));
+            name = "tick";
+            // The client went away
+            if tx.send(Ok(sse.unwrap_or_default())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
+
+fn kind_name(kind: SessionEventKind) -> &'static str {
+    match kind {
+        SessionEventKind::Started => "started",
+        SessionEventKind::Paused => "paused",
+        SessionEventKind::Resumed => "resumed",
+        SessionEventKind::Ended => "ended",
+    }
+}
@@ -1,135 +1,510 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{
+        IntoResponse,
+        sse::{KeepAlive, Sse},
+    },
 };
-use serde_json::json;
+use chrono::{Duration, Utc};
 use uuid::Uuid;
 
 use crate::{
     auth::middleware::AuthUser,
-    models::pomodoro::{EndSession, PomodoroSession, StartSession},
+    error::{AppError, Problem},
+    metrics,
+    models::pomodoro::{
+        CurrentSessionResponse, EndSession, EndSessionResponse, Interruption, InterruptionResponse,
+        InterruptionsResponse, LogInterruption, NextSession, PomodoroSession, SessionResponse,
+        SessionType, SessionsResponse, SettingsResponse, StartOptions, StartSession, StatsQuery,
+        StatsResponse, UpdatePomodoroSettings,
+    },
+    pomodoro::{
+        events::{SessionEventKind, client_stream},
+        stats::compute_stats,
+        timer::view,
+    },
+    repo::{
+        RepoError,
+        pomodoro::{Ended, NewSession, Started},
+    },
     state::AppState,
+    timezone::user_timezone,
+    validation::ValidatedJson,
 };
 
-pub async fn get_sessions(auth: AuthUser, State(state): State<AppState>) -> impl IntoResponse {
-    let sessions = sqlx::query_as::<_, PomodoroSession>(
-        "SELECT * FROM pomodoro_sessions WHERE user_id = $1 ORDER BY started_at DESC",
+#[utoipa::path(get, path = "/", tag = "pomodoro", responses((status = OK, body = SessionsResponse)))]
+pub async fn get_sessions(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let sessions = state.repos.pomodoro.list(auth.user_id).await?;
+
+    let now = Utc::now();
+    let sessions: Vec<_> = sessions.into_iter().map(|s| view(s, now)).collect();
+    Ok((StatusCode::OK, Json(SessionsResponse { sessions })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/current",
+    tag = "pomodoro",
+    responses((status = OK, body = CurrentSessionResponse))
+)]
+pub async fn get_current_session(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let session = state
+        .repos
+        .pomodoro
+        .active(auth.user_id)
+        .await?
+        .map(|s| view(s, Utc::now()));
+
+    Ok((StatusCode::OK, Json(CurrentSessionResponse { session })))
+}
+
+// Live updates for all of the user's devices; see events::client_stream
+#[utoipa::path(
+    get,
+    path = "/stream",
+    tag = "pomodoro",
+    responses((status = OK, description = "Server-sent session events", content_type = "text/event-stream"))
+)]
+pub async fn stream_sessions(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let current = state.repos.pomodoro.active(auth.user_id).await?;
+    let stream = client_stream(
+        &state.session_events,
+        state.repos.clone(),
+        auth.user_id,
+        current,
+        state.shutdown.clone(),
+    );
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/start",
+    tag = "pomodoro",
+    params(StartOptions),
+    request_body = StartSession,
+    responses(
+        (status = CREATED, body = SessionResponse),
+        (status = CONFLICT, description = "`session_active` unless `replace` is set", body = Problem,
+         content_type = "application/problem+json"),
+    )
+)]
+pub async fn start_session(
+    auth: AuthUser,
+    Query(options): Query<StartOptions>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<StartSession>,
+) -> Result<impl IntoResponse, AppError> {
+    open_session(
+        &state,
+        auth.user_id,
+        options.replace,
+        body.task_id,
+        Some(body.session_type.unwrap_or(SessionType::Focus)),
+        body.duration_minutes,
+    )
+    .await
+}
+
+// Starts whichever session type comes next in the user's cycle today
+#[utoipa::path(
+    post,
+    path = "/next",
+    tag = "pomodoro",
+    params(StartOptions),
+    request_body = NextSession,
+    responses(
+        (status = CREATED, body = SessionResponse),
+        (status = CONFLICT, description = "`session_active` unless `replace` is set", body = Problem,
+         content_type = "application/problem+json"),
+    )
+)]
+pub async fn start_next_session(
+    auth: AuthUser,
+    Query(options): Query<StartOptions>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<NextSession>,
+) -> Result<impl IntoResponse, AppError> {
+    open_session(
+        &state,
+        auth.user_id,
+        options.replace,
+        body.task_id,
+        None,
+        None,
     )
-    .bind(auth.user_id)
-    .fetch_all(&state.db_pool)
-    .await;
-
-    match sessions {
-        Ok(sessions) => (StatusCode::OK, Json(json!({"sessions": sessions}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch pomodoro sessions"})),
+    .await
+}
+
+// Only one session may be open at a time (enforced by a partial unique index);
+// with `replace` the open one is ended first in the same transaction. A missing
+// session type means the next one in the cycle, a missing duration the user's
+// setting for that type.
+async fn open_session(
+    state: &AppState,
+    user_id: Uuid,
+    replace: bool,
+    task_id: Option<Uuid>,
+    session_type: Option<SessionType>,
+    duration_minutes: Option<i32>,
+) -> Result<impl IntoResponse + use<>, AppError> {
+    let tz = user_timezone(&*state.repos.users, user_id).await?;
+    let started = state
+        .repos
+        .pomodoro
+        .start(
+            user_id,
+            tz,
+            NewSession {
+                task_id,
+                session_type,
+                duration_minutes,
+                replace,
+            },
         )
-            .into_response(),
+        .await;
+
+    let Started { session, replaced } = match started {
+        Ok(Some(started)) => started,
+        Ok(None) => return Err(AppError::NotFound("Task")),
+        Err(RepoError::Duplicate) => {
+            let active = state.repos.pomodoro.active(user_id).await?;
+            return Err(AppError::conflict(
+                "session_active",
+                "A pomodoro session is already active",
+            )
+            .with_context("session", active.map(|s| view(s, Utc::now()))));
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let events = &state.session_events;
+    if let Some(replaced) = &replaced {
+        metrics::pomodoro_ended(replaced.status, replaced.auto_closed);
+        events.publish(SessionEventKind::Ended, replaced).await;
     }
+    metrics::pomodoro_started(&session.session_type);
+    events.publish(SessionEventKind::Started, &session).await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(SessionResponse {
+            session: view(session, Utc::now()),
+        }),
+    ))
 }
 
-pub async fn start_session(
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "pomodoro",
+    responses((status = OK, description = "The session with its interruptions", body = SessionResponse))
+)]
+pub async fn get_session(
     auth: AuthUser,
+    Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    Json(body): Json<StartSession>,
-) -> impl IntoResponse {
-    let session = sqlx::query_as::<_, PomodoroSession>(
-        "INSERT INTO pomodoro_sessions (user_id, task_id, session_type, duration_minutes)
-         SELECT
-             $1,
-             CASE WHEN $2::uuid IS NULL THEN NULL ELSE t.id END,
-             COALESCE($3, 'focus'),
-             COALESCE($4, 25)
-         FROM (SELECT 1) seed
-         LEFT JOIN tasks t ON t.id = $2 AND t.user_id = $1
-         WHERE $2::uuid IS NULL OR t.id IS NOT NULL
-         RETURNING *",
+) -> Result<impl IntoResponse, AppError> {
+    let session = find_session(&state, id, auth.user_id).await?;
+    let interruptions = session_interruptions(&state, auth.user_id, id).await?;
+
+    let mut session = view(session, Utc::now());
+    session.interruptions = Some(interruptions);
+    Ok((StatusCode::OK, Json(SessionResponse { session })))
+}
+
+async fn session_interruptions(
+    state: &AppState,
+    user_id: Uuid,
+    session_id: Uuid,
+) -> Result<Vec<Interruption>, AppError> {
+    Ok(state
+        .repos
+        .pomodoro
+        .interruptions(user_id, session_id)
+        .await?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/interruptions",
+    tag = "pomodoro",
+    responses((status = OK, body = InterruptionsResponse))
+)]
+pub async fn get_interruptions(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    find_session(&state, id, auth.user_id).await?;
+    let interruptions = session_interruptions(&state, auth.user_id, id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(InterruptionsResponse { interruptions }),
+    ))
+}
+
+// An interruption has to fall inside the session: after it started and, for
+// a finished session, before it ended
+#[utoipa::path(
+    post,
+    path = "/{id}/interruptions",
+    tag = "pomodoro",
+    request_body = LogInterruption,
+    responses((status = CREATED, body = InterruptionResponse))
+)]
+pub async fn log_interruption(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<LogInterruption>,
+) -> Result<impl IntoResponse, AppError> {
+    let session = find_session(&state, id, auth.user_id).await?;
+
+    let now = Utc::now();
+    let occurred_at = body.occurred_at.unwrap_or(now);
+    let ends = session.ended_at.unwrap_or(now);
+    if occurred_at < session.started_at || occurred_at > ends {
+        return Err(AppError::field("occurred_at", "must be within the session"));
+    }
+
+    let interruption = state
+        .repos
+        .pomodoro
+        .add_interruption(auth.user_id, id, body.kind, occurred_at, body.note)
+        .await?
+        .ok_or(AppError::NotFound("Session"))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InterruptionResponse { interruption }),
+    ))
+}
+
+async fn find_session(
+    state: &AppState,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<PomodoroSession, AppError> {
+    state
+        .repos
+        .pomodoro
+        .find(user_id, id)
+        .await?
+        .ok_or(AppError::NotFound("Session"))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/pause",
+    tag = "pomodoro",
+    responses(
+        (status = OK, body = SessionResponse),
+        (status = CONFLICT, description = "`invalid_transition`", body = Problem, content_type = "application/problem+json"),
     )
-    .bind(auth.user_id)
-    .bind(body.task_id)
-    .bind(body.session_type)
-    .bind(body.duration_minutes)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match session {
-        Ok(Some(session)) => {
-            (StatusCode::CREATED, Json(json!({"session": session}))).into_response()
-        }
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Task not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to start pomodoro session"})),
-        )
-            .into_response(),
+)]
+pub async fn pause_session(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let session = state.repos.pomodoro.pause(auth.user_id, id).await?;
+
+    let Some(session) = session else {
+        return Err(transition_refused(&state, id, auth.user_id, "Session is not running").await);
+    };
+
+    state
+        .session_events
+        .publish(SessionEventKind::Paused, &session)
+        .await;
+    Ok((
+        StatusCode::OK,
+        Json(SessionResponse {
+            session: view(session, Utc::now()),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/resume",
+    tag = "pomodoro",
+    responses(
+        (status = OK, body = SessionResponse),
+        (status = CONFLICT, description = "`invalid_transition`", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn resume_session(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let session = state.repos.pomodoro.resume(auth.user_id, id).await?;
+
+    let Some(session) = session else {
+        return Err(transition_refused(&state, id, auth.user_id, "Session is not paused").await);
+    };
+
+    state
+        .session_events
+        .publish(SessionEventKind::Resumed, &session)
+        .await;
+    Ok((
+        StatusCode::OK,
+        Json(SessionResponse {
+            session: view(session, Utc::now()),
+        }),
+    ))
+}
+
+// A guarded state change matched no row: either the session doesn't exist or
+// it's in the wrong state for the transition
+async fn transition_refused(state: &AppState, id: Uuid, user_id: Uuid, detail: &str) -> AppError {
+    match find_session(state, id, user_id).await {
+        Ok(session) => AppError::conflict("invalid_transition", detail)
+            .with_context("session", view(session, Utc::now())),
+        Err(err) => err,
     }
 }
 
+// Ending an already-ended session only updates its notes. When the session ran
+// to completion and the user has auto_start_next on, the next session in the
+// cycle is started in the same transaction.
+#[utoipa::path(
+    post,
+    path = "/{id}/end",
+    tag = "pomodoro",
+    request_body = EndSession,
+    responses((status = OK, body = EndSessionResponse))
+)]
 pub async fn end_session(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    Json(body): Json<EndSession>,
-) -> impl IntoResponse {
-    let session = sqlx::query_as::<_, PomodoroSession>(
-        "UPDATE pomodoro_sessions
-         SET ended_at = COALESCE(ended_at, NOW()),
-             notes = COALESCE($3, notes)
-         WHERE id = $1 AND user_id = $2
-         RETURNING *",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .bind(body.notes)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match session {
-        Ok(Some(session)) => (StatusCode::OK, Json(json!({"session": session}))).into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Session not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to end pomodoro session"})),
-        )
-            .into_response(),
+    ValidatedJson(body): ValidatedJson<EndSession>,
+) -> Result<impl IntoResponse, AppError> {
+    let tz = user_timezone(&*state.repos.users, auth.user_id).await?;
+    let Ended {
+        session,
+        just_ended,
+        next,
+    } = state
+        .repos
+        .pomodoro
+        .end(auth.user_id, id, tz, body.notes)
+        .await?
+        .ok_or(AppError::NotFound("Session"))?;
+
+    let events = &state.session_events;
+    if just_ended {
+        metrics::pomodoro_ended(session.status, session.auto_closed);
+        events.publish(SessionEventKind::Ended, &session).await;
+    }
+    if let Some(next) = &next {
+        metrics::pomodoro_started(&next.session_type);
+        events.publish(SessionEventKind::Started, next).await;
+    }
+    let now = Utc::now();
+    Ok((
+        StatusCode::OK,
+        Json(EndSessionResponse {
+            session: view(session, now),
+            next: next.map(|s| view(s, now)),
+        }),
+    ))
+}
+
+#[utoipa::path(get, path = "/settings", tag = "pomodoro", responses((status = OK, body = SettingsResponse)))]
+pub async fn get_settings(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let settings = state.repos.pomodoro.settings(auth.user_id).await?;
+
+    Ok((StatusCode::OK, Json(SettingsResponse { settings })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/settings",
+    tag = "pomodoro",
+    request_body = UpdatePomodoroSettings,
+    responses((status = OK, body = SettingsResponse))
+)]
+pub async fn update_settings(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<UpdatePomodoroSettings>,
+) -> Result<impl IntoResponse, AppError> {
+    let settings = state
+        .repos
+        .pomodoro
+        .update_settings(auth.user_id, body)
+        .await?;
+
+    Ok((StatusCode::OK, Json(SettingsResponse { settings })))
+}
+
+// Defaults to the 30 days up to and including today in the user's timezone
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "pomodoro",
+    params(StatsQuery),
+    responses((status = OK, body = StatsResponse))
+)]
+pub async fn get_stats(
+    auth: AuthUser,
+    Query(query): Query<StatsQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let tz = user_timezone(&*state.repos.users, auth.user_id).await?;
+    let to = query
+        .to
+        .unwrap_or_else(|| Utc::now().with_timezone(&tz).date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(29));
+    if from > to {
+        return Err(AppError::field("from", "must not be after to"));
     }
+
+    let sessions = state
+        .repos
+        .pomodoro
+        .focus_sessions(auth.user_id, tz, from, to)
+        .await?;
+    let interruptions = state
+        .repos
+        .pomodoro
+        .focus_interruptions(auth.user_id, tz, from, to)
+        .await?;
+
+    let stats = compute_stats(&sessions, &interruptions, tz, from, to, query.group_by);
+    Ok((StatusCode::OK, Json(StatsResponse { stats })))
 }
 
+#[utoipa::path(delete, path = "/{id}", tag = "pomodoro", responses((status = NO_CONTENT)))]
 pub async fn delete_session(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    let deleted = sqlx::query_scalar::<_, Uuid>(
-        "DELETE FROM pomodoro_sessions WHERE id = $1 AND user_id = $2 RETURNING id",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match deleted {
-        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Session not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to delete pomodoro session"})),
-        )
-            .into_response(),
+) -> Result<impl IntoResponse, AppError> {
+    if !state.repos.pomodoro.delete(auth.user_id, id).await? {
+        return Err(AppError::NotFound("Session"));
     }
+
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -1,2 +1,7 @@
+pub mod cycle;
+pub mod events;
 pub mod handlers;
+pub mod reaper;
 pub mod router;
+pub mod stats;
+pub mod timer;
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+
+use crate::{
+    metrics,
+    models::pomodoro::PomodoroSession,
+    pomodoro::events::{SessionEventKind, SessionEvents},
+    shutdown::Shutdown,
+};
+
+const INTERVAL: Duration = Duration::from_secs(60);
+// How long past its planned end a running session may stay open
+const GRACE_MINUTES: i32 = 10;
+// How long a session may sit paused before it's given up on
+const PAUSE_LIMIT_MINUTES: i32 = 120;
+const BATCH_SIZE: i64 = 100;
+
+// Closes sessions whose client went away without ending them. A running
+// session ends at its planned end (start + duration + time paused), a paused
+// one when it was paused; either way it's classified like a normal end and
+// flagged auto_closed. SKIP LOCKED lets several instances run this at once.
+// Stops at the next tick after shutdown, never in the middle of a round.
+pub async fn run(pool: PgPool, events: SessionEvents, shutdown: Shutdown) {
+    let mut ticks = tokio::time::interval(INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticks.tick() => {}
+            _ = shutdown.triggered() => return,
+        }
+        loop {
+            match close_stale_sessions(&pool).await {
+                Ok(closed) => {
+                    for session in &closed {
+                        metrics::pomodoro_ended(session.status, session.auto_closed);
+                        events.publish(SessionEventKind::Ended, session).await;
+                    }
+                    if (closed.len() as i64) < BATCH_SIZE {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(error = %err, "failed to close stale pomodoro sessions");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn close_stale_sessions(pool: &PgPool) -> Result<Vec<PomodoroSession>, sqlx::Error> {
+    sqlx::query_as::<_, PomodoroSession>(
+        "WITH stale AS (
+             SELECT id,
+                    CASE
+                        WHEN status = 'paused' THEN paused_at
+                        ELSE started_at
+                             + make_interval(mins => duration_minutes, secs => paused_seconds)
+                    END AS planned_end
+             FROM pomodoro_sessions
+             WHERE ended_at IS NULL
+               AND (
+                   (status = 'running'
+                    AND started_at + make_interval(mins => duration_minutes, secs => paused_seconds)
+                        < NOW() - make_interval(mins => $1))
+                   OR (status = 'paused' AND paused_at < NOW() - make_interval(mins => $2))
+               )
+             ORDER BY started_at
+             LIMIT $3
+             FOR UPDATE SKIP LOCKED
+         )
+         UPDATE pomodoro_sessions ps
+         SET ended_at = stale.planned_end,
+             paused_at = NULL,
+             status = CASE
+                 WHEN EXTRACT(EPOCH FROM stale.planned_end - ps.started_at) - ps.paused_seconds
+                      >= ps.duration_minutes * 60
+                 THEN 'completed'
+                 ELSE 'interrupted'
+             END,
+             auto_closed = TRUE
+         FROM stale
+         WHERE ps.id = stale.id
+         RETURNING ps.*",
+    )
+    .bind(GRACE_MINUTES)
+    .bind(PAUSE_LIMIT_MINUTES)
+    .bind(BATCH_SIZE)
+    .fetch_all(pool)
+    .await
+}
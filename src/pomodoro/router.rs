@@ -1,15 +1,23 @@
-use axum::{
-    Router,
-    routing::{delete, get, post},
-};
+use utoipa_axum::routes;
 
-use crate::pomodoro::handlers::{delete_session, end_session, get_sessions, start_session};
-use crate::state::AppState;
+use crate::openapi::ApiRouter;
+use crate::pomodoro::handlers;
 
-pub fn pomodoro_router() -> Router<AppState> {
-    Router::new()
-        .route("/", get(get_sessions))
-        .route("/start", post(start_session))
-        .route("/{id}/end", post(end_session))
-        .route("/{id}", delete(delete_session))
+pub fn pomodoro_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(handlers::get_sessions))
+        .routes(routes!(handlers::start_session))
+        .routes(routes!(handlers::start_next_session))
+        .routes(routes!(handlers::get_current_session))
+        .routes(routes!(handlers::stream_sessions))
+        .routes(routes!(handlers::get_stats))
+        .routes(routes!(handlers::get_settings, handlers::update_settings))
+        .routes(routes!(handlers::end_session))
+        .routes(routes!(handlers::get_session, handlers::delete_session))
+        .routes(routes!(handlers::pause_session))
+        .routes(routes!(handlers::resume_session))
+        .routes(routes!(
+            handlers::get_interruptions,
+            handlers::log_interruption
+        ))
 }
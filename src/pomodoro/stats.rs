@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use chrono::{Datelike, Duration, NaiveDate, Timelike, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    models::pomodoro::{
+        FocusGroup, FocusSession, FocusTotals, Interruption, InterruptionKind, InterruptionReason,
+        InterruptionStats, PomodoroStats, SessionStatus, StatsGrouping,
+    },
+    pomodoro::timer::elapsed_seconds,
+};
+
+const TOP_REASONS: usize = 5;
+
+#[derive(Default)]
+struct Tally {
+    seconds: i64,
+    sessions: i64,
+    completed: i64,
+    interrupted: i64,
+    interruptions: i64,
+}
+
+impl Tally {
+    fn add(&mut self, status: SessionStatus, seconds: i64, interruptions: i64) {
+        self.seconds += seconds;
+        self.sessions += 1;
+        self.interruptions += interruptions;
+        match status {
+            SessionStatus::Completed => self.completed += 1,
+            SessionStatus::Interrupted => self.interrupted += 1,
+            SessionStatus::Running | SessionStatus::Paused => {}
+        }
+    }
+
+    fn totals(&self) -> FocusTotals {
+        FocusTotals {
+            focus_minutes: minutes(self.seconds),
+            sessions: self.sessions,
+            completed: self.completed,
+            interrupted: self.interrupted,
+            interruptions: self.interruptions,
+        }
+    }
+}
+
+// Minutes to one decimal place
+fn minutes(seconds: i64) -> f64 {
+    (seconds as f64 / 6.0).round() / 10.0
+}
+
+// `sessions` are the finished focus sessions that started between `from` and
+// `to` on the user's calendar, `interruptions` the ones logged against them.
+// Focus time is active time, so pauses don't count.
+pub fn compute_stats(
+    sessions: &[FocusSession],
+    interruptions: &[Interruption],
+    tz: Tz,
+    from: NaiveDate,
+    to: NaiveDate,
+    group_by: StatsGrouping,
+) -> PomodoroStats {
+    let now = Utc::now();
+    let mut per_session: HashMap<Uuid, i64> = HashMap::new();
+    for interruption in interruptions {
+        *per_session.entry(interruption.session_id).or_default() += 1;
+    }
+
+    let mut total = Tally::default();
+    let mut hours = [0i64; 24];
+    let mut groups: HashMap<Option<String>, (Option<String>, Tally)> = HashMap::new();
+    let mut tasks: HashMap<Option<String>, (Option<String>, Tally)> = HashMap::new();
+
+    for focus in sessions {
+        let session = &focus.session;
+        let seconds = elapsed_seconds(session, now);
+        let started = session.started_at.with_timezone(&tz);
+        let task_key = session.task_id.map(|id| id.to_string());
+        let interruption_count = per_session.get(&session.id).copied().unwrap_or(0);
+
+        total.add(session.status, seconds, interruption_count);
+        // The whole session counts towards the hour it started in
+        hours[started.hour() as usize] += seconds;
+        tasks
+            .entry(task_key.clone())
+            .or_insert_with(|| (focus.task_title.clone(), Tally::default()))
+            .1
+            .add(session.status, seconds, interruption_count);
+
+        let date = started.date_naive();
+        let keys: Vec<(Option<String>, Option<String>)> = match group_by {
+            StatsGrouping::Day => vec![(Some(date.to_string()), None)],
+            StatsGrouping::Week => {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                vec![(Some(monday.to_string()), None)]
+            }
+            StatsGrouping::Month => {
+                vec![(
+                    Some(format!("{:04}-{:02}", date.year(), date.month())),
+                    None,
+                )]
+            }
+            StatsGrouping::Task => vec![(task_key, focus.task_title.clone())],
+            // A session on a task with several tags counts once for each tag
+            StatsGrouping::Tag => match focus.task_tags.as_deref() {
+                Some(tags) if !tags.is_empty() => {
+                    tags.iter().map(|tag| (Some(tag.clone()), None)).collect()
+                }
+                _ => vec![(None, None)],
+            },
+        };
+        for (key, label) in keys {
+            groups
+                .entry(key)
+                .or_insert_with(|| (label, Tally::default()))
+                .1
+                .add(session.status, seconds, interruption_count);
+        }
+    }
+
+    let into_groups = |tallies: HashMap<Option<String>, (Option<String>, Tally)>| {
+        tallies
+            .into_iter()
+            .map(|(key, (label, tally))| FocusGroup {
+                key,
+                label,
+                totals: tally.totals(),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Calendar groups read in date order, the others biggest first
+    let mut groups = into_groups(groups);
+    match group_by {
+        StatsGrouping::Day | StatsGrouping::Week | StatsGrouping::Month => {
+            groups.sort_by(|a, b| a.key.cmp(&b.key))
+        }
+        StatsGrouping::Task | StatsGrouping::Tag => groups.sort_by(|a, b| {
+            b.totals
+                .focus_minutes
+                .total_cmp(&a.totals.focus_minutes)
+                .then_with(|| a.key.cmp(&b.key))
+        }),
+    }
+    let mut by_task = into_groups(tasks);
+    by_task.sort_by(|a, b| {
+        b.totals
+            .focus_minutes
+            .total_cmp(&a.totals.focus_minutes)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+
+    let best_hour = (0..24u32)
+        .filter(|&hour| hours[hour as usize] > 0)
+        .max_by_key(|&hour| (hours[hour as usize], std::cmp::Reverse(hour)));
+
+    PomodoroStats {
+        interruptions: interruption_stats(interruptions),
+        from,
+        to,
+        group_by,
+        totals: total.totals(),
+        average_session_minutes: if total.sessions == 0 {
+            0.0
+        } else {
+            minutes(total.seconds / total.sessions)
+        },
+        hour_histogram: hours.iter().map(|&seconds| minutes(seconds)).collect(),
+        best_hour,
+        groups,
+        by_task,
+    }
+}
+
+// Reasons are the interruption notes, compared case-insensitively
+fn interruption_stats(interruptions: &[Interruption]) -> InterruptionStats {
+    let count = |kind| interruptions.iter().filter(|i| i.kind == kind).count() as i64;
+
+    let mut reasons: HashMap<String, i64> = HashMap::new();
+    for note in interruptions.iter().filter_map(|i| i.note.as_deref()) {
+        let reason = note.trim().to_lowercase();
+        if !reason.is_empty() {
+            *reasons.entry(reason).or_default() += 1;
+        }
+    }
+    let mut top_reasons: Vec<_> = reasons
+        .into_iter()
+        .map(|(reason, count)| InterruptionReason { reason, count })
+        .collect();
+    top_reasons.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.reason.cmp(&b.reason)));
+    top_reasons.truncate(TOP_REASONS);
+
+    InterruptionStats {
+        internal: count(InterruptionKind::Internal),
+        external: count(InterruptionKind::External),
+        top_reasons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::models::pomodoro::{PomodoroSession, SessionType};
+
+    // Tallinn is two hours ahead of UTC in winter
+    const TZ: Tz = chrono_tz::Europe::Tallinn;
+
+    fn at(utc: &str) -> DateTime<Utc> {
+        utc.parse().unwrap()
+    }
+
+    fn focus(started: &str, minutes: i64) -> FocusSession {
+        let started_at = at(started);
+        FocusSession {
+            session: PomodoroSession {
+                id: Uuid::new_v4(),
+                user_id: Uuid::nil(),
+                task_id: None,
+                session_type: SessionType::Focus,
+                duration_minutes: 25,
+                started_at,
+                ended_at: Some(started_at + Duration::minutes(minutes)),
+                notes: None,
+                status: SessionStatus::Completed,
+                paused_at: None,
+                paused_seconds: 0,
+                auto_closed: false,
+            },
+            task_title: None,
+            task_tags: None,
+        }
+    }
+
+    fn on_task(mut focus: FocusSession, task_id: Uuid, title: &str, tags: &[&str]) -> FocusSession {
+        focus.session.task_id = Some(task_id);
+        focus.task_title = Some(title.to_string());
+        focus.task_tags = Some(tags.iter().map(|tag| tag.to_string()).collect());
+        focus
+    }
+
+    fn interruption(
+        session: &FocusSession,
+        kind: InterruptionKind,
+        note: Option<&str>,
+    ) -> Interruption {
+        Interruption {
+            id: Uuid::new_v4(),
+            session_id: session.session.id,
+            kind,
+            occurred_at: session.session.started_at,
+            note: note.map(str::to_string),
+            created_at: session.session.started_at,
+        }
+    }
+
+    fn stats(sessions: &[FocusSession], group_by: StatsGrouping) -> PomodoroStats {
+        stats_with(sessions, &[], group_by)
+    }
+
+    fn stats_with(
+        sessions: &[FocusSession],
+        interruptions: &[Interruption],
+        group_by: StatsGrouping,
+    ) -> PomodoroStats {
+        let (from, to) = ("2026-01-01".parse().unwrap(), "2026-02-28".parse().unwrap());
+        compute_stats(sessions, interruptions, TZ, from, to, group_by)
+    }
+
+    fn keys(stats: &PomodoroStats) -> Vec<Option<&str>> {
+        stats.groups.iter().map(|g| g.key.as_deref()).collect()
+    }
+
+    #[test]
+    fn calendar_groups_follow_the_users_timezone() {
+        // Sunday 22:30 UTC is already Monday 00:30 in Tallinn, and the last
+        // evening of January UTC is February there
+        let sessions = [
+            focus("2026-01-04T22:30:00Z", 25),
+            focus("2026-01-04T12:00:00Z", 25),
+            focus("2026-01-31T23:00:00Z", 50),
+        ];
+
+        let by_day = stats(&sessions, StatsGrouping::Day);
+        assert_eq!(
+            keys(&by_day),
+            [Some("2026-01-04"), Some("2026-01-05"), Some("2026-02-01")]
+        );
+
+        let by_week = stats(&sessions, StatsGrouping::Week);
+        assert_eq!(
+            keys(&by_week),
+            [Some("2025-12-29"), Some("2026-01-05"), Some("2026-01-26")]
+        );
+
+        let by_month = stats(&sessions, StatsGrouping::Month);
+        assert_eq!(keys(&by_month), [Some("2026-01"), Some("2026-02")]);
+        assert_eq!(by_month.groups[0].totals.sessions, 2);
+        assert_eq!(by_month.groups[1].totals.focus_minutes, 50.0);
+    }
+
+    #[test]
+    fn hour_histogram_uses_local_start_hours() {
+        // 07:50 UTC starts at 09:50 local; the whole hour-long session counts there
+        let sessions = [
+            focus("2026-01-05T07:50:00Z", 60),
+            focus("2026-01-05T22:10:00Z", 25),
+        ];
+        let stats = stats(&sessions, StatsGrouping::Day);
+
+        assert_eq!(stats.hour_histogram.len(), 24);
+        assert_eq!(stats.hour_histogram[9], 60.0);
+        assert_eq!(stats.hour_histogram[0], 25.0);
+        assert_eq!(stats.hour_histogram.iter().sum::<f64>(), 85.0);
+        assert_eq!(stats.best_hour, Some(9));
+        assert_eq!(stats.average_session_minutes, 42.5);
+    }
+
+    #[test]
+    fn best_hour_ties_go_to_the_earliest_hour() {
+        let sessions = [
+            focus("2026-01-05T12:00:00Z", 25),
+            focus("2026-01-05T07:00:00Z", 25),
+        ];
+        assert_eq!(stats(&sessions, StatsGrouping::Day).best_hour, Some(9));
+        assert_eq!(stats(&[], StatsGrouping::Day).best_hour, None);
+    }
+
+    #[test]
+    fn tasks_are_grouped_biggest_first() {
+        let (report, review) = (Uuid::new_v4(), Uuid::new_v4());
+        let sessions = [
+            on_task(focus("2026-01-05T08:00:00Z", 25), report, "Report", &[]),
+            on_task(focus("2026-01-05T09:00:00Z", 25), report, "Report", &[]),
+            on_task(focus("2026-01-05T10:00:00Z", 30), review, "Review", &[]),
+            focus("2026-01-05T11:00:00Z", 10),
+        ];
+        let stats = stats(&sessions, StatsGrouping::Task);
+
+        let labels: Vec<_> = stats.groups.iter().map(|g| g.label.as_deref()).collect();
+        assert_eq!(labels, [Some("Report"), Some("Review"), None]);
+        assert_eq!(stats.groups[0].key, Some(report.to_string()));
+        assert_eq!(stats.groups[0].totals.sessions, 2);
+        // by_task is the same breakdown whatever the grouping
+        let by_task: Vec<_> = stats
+            .by_task
+            .iter()
+            .map(|g| g.totals.focus_minutes)
+            .collect();
+        assert_eq!(by_task, [50.0, 30.0, 10.0]);
+    }
+
+    #[test]
+    fn a_session_counts_once_for_each_tag_of_its_task() {
+        let task = Uuid::new_v4();
+        let sessions = [
+            on_task(
+                focus("2026-01-05T08:00:00Z", 25),
+                task,
+                "Essay",
+                &["writing", "deep"],
+            ),
+            on_task(
+                focus("2026-01-05T09:00:00Z", 20),
+                Uuid::new_v4(),
+                "Notes",
+                &["writing"],
+            ),
+            focus("2026-01-05T10:00:00Z", 25),
+        ];
+        let stats = stats(&sessions, StatsGrouping::Tag);
+
+        // Ties on minutes are broken by key, and untagged sessions sort first
+        assert_eq!(keys(&stats), [Some("writing"), None, Some("deep")]);
+        assert_eq!(stats.groups[0].totals.sessions, 2);
+        assert_eq!(stats.groups[0].totals.focus_minutes, 45.0);
+        // Overall totals count each session once
+        assert_eq!(stats.totals.sessions, 3);
+        assert_eq!(stats.totals.focus_minutes, 70.0);
+    }
+
+    #[test]
+    fn interruption_reasons_are_ranked_by_count_then_name() {
+        let session = focus("2026-01-05T08:00:00Z", 25);
+        let other = focus("2026-01-05T09:00:00Z", 25);
+        let notes = [
+            Some("Slack"),
+            Some(" slack "),
+            Some("SLACK"),
+            Some("phone"),
+            Some("Phone"),
+            Some("door"),
+            Some("email"),
+            Some("coffee"),
+            Some("bell"),
+            Some("   "),
+            None,
+        ];
+        let mut interruptions: Vec<_> = notes
+            .iter()
+            .map(|note| interruption(&session, InterruptionKind::External, *note))
+            .collect();
+        interruptions.push(interruption(
+            &other,
+            InterruptionKind::Internal,
+            Some("phone"),
+        ));
+
+        let stats = stats_with(&[session, other], &interruptions, StatsGrouping::Day);
+        let reasons = &stats.interruptions;
+        assert_eq!(reasons.external, 11);
+        assert_eq!(reasons.internal, 1);
+        let ranked: Vec<_> = reasons
+            .top_reasons
+            .iter()
+            .map(|r| (r.reason.as_str(), r.count))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                ("phone", 3),
+                ("slack", 3),
+                ("bell", 1),
+                ("coffee", 1),
+                ("door", 1)
+            ]
+        );
+        assert_eq!(stats.totals.interruptions, 12);
+        assert_eq!(stats.groups[0].totals.interruptions, 12);
+    }
+}
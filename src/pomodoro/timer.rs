@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::pomodoro::{PomodoroSession, SessionView};
+
+// Time the timer has actually been running: pauses don't count, and the clock
+// stops at ended_at or, while paused, at paused_at.
+pub fn elapsed_seconds(session: &PomodoroSession, now: DateTime<Utc>) -> i64 {
+    let until = session.ended_at.or(session.paused_at).unwrap_or(now);
+    let wall = (until - session.started_at).num_seconds();
+    (wall - session.paused_seconds as i64).max(0)
+}
+
+pub fn view(session: PomodoroSession, now: DateTime<Utc>) -> SessionView {
+    let elapsed_seconds = elapsed_seconds(&session, now);
+    let remaining_seconds = (session.duration_minutes as i64 * 60 - elapsed_seconds).max(0);
+    SessionView {
+        session,
+        elapsed_seconds,
+        remaining_seconds,
+        interruptions: None,
+    }
+}
@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    models::goal::{CreateGoal, Goal, UpdateGoal},
+    repo::RepoResult,
+};
+
+// A goal with the counts insights::forecast projects from
+#[derive(Debug, sqlx::FromRow)]
+pub struct GoalProgress {
+    #[sqlx(flatten)]
+    pub goal: Goal,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub recent_completions: i64,
+}
+
+#[async_trait]
+pub trait GoalRepo: Send + Sync {
+    // Oldest first; recent completions are those on a date after `recent_after`
+    // in the user's timezone `tz`
+    async fn list_with_progress(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        recent_after: NaiveDate,
+    ) -> RepoResult<Vec<GoalProgress>>;
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Goal>>;
+
+    async fn create(&self, user_id: Uuid, goal: CreateGoal) -> RepoResult<Goal>;
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateGoal,
+    ) -> RepoResult<Option<Goal>>;
+
+    async fn complete(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Goal>>;
+
+    // Linked tasks are kept, unlinked
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool>;
+}
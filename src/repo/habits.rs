@@ -0,0 +1,169 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        habit::{CreateHabit, Habit, HabitCompletion, HabitPause, HabitSkip, UpdateHabit},
+        vacation::{CreateVacation, Vacation},
+    },
+    repo::RepoResult,
+};
+
+pub struct Freeze {
+    pub skip: HabitSkip,
+    pub streak_freezes: i32,
+}
+
+// Vacations live here too since all they do is excuse habits. Everything
+// about a habit is scoped to its owner: reads of someone else's habit come
+// back empty and writes to it return None.
+#[async_trait]
+pub trait HabitRepo: Send + Sync {
+    async fn list(&self, user_id: Uuid, include_archived: bool) -> RepoResult<Vec<Habit>>;
+
+    // Neither archived nor paused through `today`
+    async fn active(&self, user_id: Uuid, today: NaiveDate) -> RepoResult<Vec<Habit>>;
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Habit>>;
+
+    // A missing frequency means daily
+    async fn create(&self, user_id: Uuid, habit: CreateHabit) -> RepoResult<Habit>;
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateHabit,
+    ) -> RepoResult<Option<Habit>>;
+
+    // Completions and skips go with the habit
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool>;
+
+    // Archiving an archived habit keeps its original archived_at
+    async fn set_archived(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        archived: bool,
+    ) -> RepoResult<Option<Habit>>;
+
+    // Pauses the habit from..=until, replacing whatever pause was still to come
+    async fn pause(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> RepoResult<Option<Habit>>;
+
+    // Ends the pause: `today` and later are no longer paused, earlier paused
+    // days stay recorded
+    async fn resume(&self, user_id: Uuid, id: Uuid, today: NaiveDate) -> RepoResult<Option<Habit>>;
+
+    // Every pause of the user's habits, including ended ones
+    async fn user_pauses(&self, user_id: Uuid) -> RepoResult<Vec<HabitPause>>;
+
+    // Err(Duplicate) when the day already has a completion. `same_day` marks a
+    // check-in made on the day it's for.
+    async fn add_completion(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        value: Option<f64>,
+        same_day: bool,
+    ) -> RepoResult<Option<HabitCompletion>>;
+
+    // Adds to the day's value, creating its completion on the first entry. The
+    // day only stays same_day if every entry was.
+    async fn add_measurement(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        value: f64,
+        same_day: bool,
+    ) -> RepoResult<Option<HabitCompletion>>;
+
+    // Oldest first, optionally limited to from..=to
+    async fn completions(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> RepoResult<Vec<HabitCompletion>>;
+
+    // Across all of the user's habits, for streaks
+    async fn user_completions(&self, user_id: Uuid) -> RepoResult<Vec<HabitCompletion>>;
+
+    async fn delete_completion(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+    ) -> RepoResult<bool>;
+
+    // Oldest first, optionally limited to from..=to
+    async fn skips(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> RepoResult<Vec<HabitSkip>>;
+
+    async fn user_skips(&self, user_id: Uuid) -> RepoResult<Vec<HabitSkip>>;
+
+    // None when the habit isn't the user's; Err(Duplicate) when the day is already skipped
+    async fn skip(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        note: Option<String>,
+    ) -> RepoResult<Option<HabitSkip>>;
+
+    // Spends one of the user's streak freezes on the day, or none at all if the
+    // day is already skipped (Err(Duplicate)). None when the habit isn't the
+    // user's or no freezes are left.
+    async fn freeze(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+    ) -> RepoResult<Option<Freeze>>;
+
+    // A deleted freeze goes back to the user unless they already hold `max_freezes`
+    async fn delete_skip(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        max_freezes: i32,
+    ) -> RepoResult<bool>;
+
+    // Gives the user one more streak freeze for the habit's streak period
+    // starting `period_start`, unless that period already paid out or they
+    // already hold `max`. Either way the period counts as paid out after;
+    // someone else's habit pays out nothing and records nothing.
+    async fn award_streak_freeze(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        period_start: NaiveDate,
+        max: i32,
+    ) -> RepoResult<bool>;
+
+    // Earliest first
+    async fn vacations(&self, user_id: Uuid) -> RepoResult<Vec<Vacation>>;
+
+    async fn create_vacation(
+        &self,
+        user_id: Uuid,
+        vacation: CreateVacation,
+    ) -> RepoResult<Vacation>;
+
+    async fn delete_vacation(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool>;
+}
@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    models::goal::{CreateGoal, Goal, GoalStatus, UpdateGoal},
+    repo::{
+        RepoResult,
+        goals::{GoalProgress, GoalRepo},
+        memory::{Store, now},
+    },
+};
+
+#[async_trait]
+impl GoalRepo for Store {
+    async fn list_with_progress(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        recent_after: NaiveDate,
+    ) -> RepoResult<Vec<GoalProgress>> {
+        let tables = self.lock();
+        let mut rows: Vec<GoalProgress> = tables
+            .goals
+            .iter()
+            .filter(|g| g.user_id == user_id)
+            .map(|goal| {
+                let tasks = tables.tasks.iter().filter(|t| t.goal_id == Some(goal.id));
+                let completed = tasks.clone().filter(|t| t.completed);
+                GoalProgress {
+                    goal: goal.clone(),
+                    total_tasks: tasks.count() as i64,
+                    completed_tasks: completed.clone().count() as i64,
+                    recent_completions: completed
+                        .filter(|t| {
+                            t.completed_at
+                                .is_some_and(|at| at.with_timezone(&tz).date_naive() > recent_after)
+                        })
+                        .count() as i64,
+                }
+            })
+            .collect();
+        rows.sort_by_key(|row| row.goal.created_at);
+        Ok(rows)
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Goal>> {
+        Ok(self
+            .lock()
+            .goals
+            .iter()
+            .find(|g| g.id == id && g.user_id == user_id)
+            .cloned())
+    }
+
+    async fn create(&self, user_id: Uuid, goal: CreateGoal) -> RepoResult<Goal> {
+        let goal = Goal {
+            id: Uuid::new_v4(),
+            user_id,
+            title: goal.title,
+            description: goal.description,
+            deadline: goal.deadline,
+            status: GoalStatus::Active,
+            created_at: now(),
+        };
+        self.lock().goals.push(goal.clone());
+        Ok(goal)
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateGoal,
+    ) -> RepoResult<Option<Goal>> {
+        let mut tables = self.lock();
+        let Some(goal) = tables
+            .goals
+            .iter_mut()
+            .find(|g| g.id == id && g.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+
+        if let Some(title) = changes.title {
+            goal.title = title;
+        }
+        if let Some(description) = changes.description {
+            goal.description = Some(description);
+        }
+        if let Some(deadline) = changes.deadline {
+            goal.deadline = Some(deadline);
+        }
+        if let Some(status) = changes.status {
+            goal.status = status;
+        }
+        Ok(Some(goal.clone()))
+    }
+
+    async fn complete(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Goal>> {
+        let mut tables = self.lock();
+        let Some(goal) = tables
+            .goals
+            .iter_mut()
+            .find(|g| g.id == id && g.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+
+        goal.status = GoalStatus::Completed;
+        Ok(Some(goal.clone()))
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        let before = tables.goals.len();
+        tables
+            .goals
+            .retain(|g| !(g.id == id && g.user_id == user_id));
+        if tables.goals.len() == before {
+            return Ok(false);
+        }
+
+        // ON DELETE SET NULL for linked tasks
+        for task in tables.tasks.iter_mut().filter(|t| t.goal_id == Some(id)) {
+            task.goal_id = None;
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,543 @@
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        habit::{
+            CreateHabit, Frequency, Habit, HabitCompletion, HabitPause, HabitSkip, SkipKind,
+            UpdateHabit,
+        },
+        vacation::{CreateVacation, Vacation},
+    },
+    repo::{
+        RepoError, RepoResult,
+        habits::{Freeze, HabitRepo},
+        memory::{Store, Tables, now},
+    },
+};
+
+impl Tables {
+    fn owns_habit(&self, user_id: Uuid, habit_id: Uuid) -> bool {
+        self.habits
+            .iter()
+            .any(|h| h.id == habit_id && h.user_id == user_id)
+    }
+
+    fn habit_mut(&mut self, user_id: Uuid, id: Uuid) -> Option<&mut Habit> {
+        self.habits
+            .iter_mut()
+            .find(|h| h.id == id && h.user_id == user_id)
+    }
+
+    fn user_habit_ids(&self, user_id: Uuid) -> Vec<Uuid> {
+        self.habits
+            .iter()
+            .filter(|h| h.user_id == user_id)
+            .map(|h| h.id)
+            .collect()
+    }
+
+    // Cuts the habit's pauses short so that none covers `from` or later
+    fn end_pauses(&mut self, habit_id: Uuid, from: NaiveDate) {
+        self.pauses
+            .retain(|p| !(p.habit_id == habit_id && p.starts_on >= from));
+        for pause in self
+            .pauses
+            .iter_mut()
+            .filter(|p| p.habit_id == habit_id && p.ends_on >= from)
+        {
+            pause.ends_on = from - Duration::days(1);
+        }
+    }
+
+    // UNIQUE (habit_id, skipped_on)
+    fn insert_skip(
+        &mut self,
+        habit_id: Uuid,
+        on: NaiveDate,
+        kind: SkipKind,
+        note: Option<String>,
+    ) -> Result<HabitSkip, RepoError> {
+        if self
+            .skips
+            .iter()
+            .any(|s| s.habit_id == habit_id && s.skipped_on == on)
+        {
+            return Err(RepoError::Duplicate);
+        }
+        let skip = HabitSkip {
+            id: Uuid::new_v4(),
+            habit_id,
+            skipped_on: on,
+            kind,
+            note,
+            created_at: now(),
+        };
+        self.skips.push(skip.clone());
+        Ok(skip)
+    }
+}
+
+fn in_range(date: NaiveDate, from: Option<NaiveDate>, to: Option<NaiveDate>) -> bool {
+    from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+}
+
+#[async_trait]
+impl HabitRepo for Store {
+    async fn list(&self, user_id: Uuid, include_archived: bool) -> RepoResult<Vec<Habit>> {
+        Ok(self
+            .lock()
+            .habits
+            .iter()
+            .filter(|h| h.user_id == user_id && (h.archived_at.is_none() || include_archived))
+            .cloned()
+            .collect())
+    }
+
+    async fn active(&self, user_id: Uuid, today: NaiveDate) -> RepoResult<Vec<Habit>> {
+        Ok(self
+            .lock()
+            .habits
+            .iter()
+            .filter(|h| {
+                h.user_id == user_id
+                    && h.archived_at.is_none()
+                    && h.paused_until.is_none_or(|until| until < today)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Habit>> {
+        Ok(self
+            .lock()
+            .habits
+            .iter()
+            .find(|h| h.id == id && h.user_id == user_id)
+            .cloned())
+    }
+
+    async fn create(&self, user_id: Uuid, habit: CreateHabit) -> RepoResult<Habit> {
+        let habit = Habit {
+            id: Uuid::new_v4(),
+            user_id,
+            name: habit.name,
+            frequency: habit.frequency.unwrap_or(Frequency::Daily),
+            created_at: now(),
+            weekdays: habit.weekdays,
+            target_count: habit.target_count,
+            interval_days: habit.interval_days,
+            unit: habit.unit,
+            daily_target: habit.daily_target,
+            archived_at: None,
+            paused_until: None,
+        };
+        self.lock().habits.push(habit.clone());
+        Ok(habit)
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateHabit,
+    ) -> RepoResult<Option<Habit>> {
+        let mut tables = self.lock();
+        let Some(habit) = tables.habit_mut(user_id, id) else {
+            return Ok(None);
+        };
+
+        if let Some(name) = changes.name {
+            habit.name = name;
+        }
+        if let Some(frequency) = changes.frequency {
+            habit.frequency = frequency;
+        }
+        if let Some(weekdays) = changes.weekdays {
+            habit.weekdays = Some(weekdays);
+        }
+        if let Some(target_count) = changes.target_count {
+            habit.target_count = Some(target_count);
+        }
+        if let Some(interval_days) = changes.interval_days {
+            habit.interval_days = Some(interval_days);
+        }
+        if let Some(unit) = changes.unit {
+            habit.unit = Some(unit);
+        }
+        if let Some(daily_target) = changes.daily_target {
+            habit.daily_target = Some(daily_target);
+        }
+        Ok(Some(habit.clone()))
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, id) {
+            return Ok(false);
+        }
+
+        // Postgres only hides the row until purge-deleted; nothing can read it back,
+        // so dropping it here (ON DELETE CASCADE for completions, skips, pauses
+        // and freeze awards) looks the same
+        tables.habits.retain(|h| h.id != id);
+        tables.completions.retain(|c| c.habit_id != id);
+        tables.skips.retain(|s| s.habit_id != id);
+        tables.pauses.retain(|p| p.habit_id != id);
+        tables.freeze_awards.retain(|(habit_id, _)| *habit_id != id);
+        Ok(true)
+    }
+
+    async fn set_archived(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        archived: bool,
+    ) -> RepoResult<Option<Habit>> {
+        let mut tables = self.lock();
+        let Some(habit) = tables.habit_mut(user_id, id) else {
+            return Ok(None);
+        };
+
+        habit.archived_at = if archived {
+            habit.archived_at.or_else(|| Some(now()))
+        } else {
+            None
+        };
+        Ok(Some(habit.clone()))
+    }
+
+    async fn pause(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> RepoResult<Option<Habit>> {
+        let mut tables = self.lock();
+        let Some(habit) = tables.habit_mut(user_id, id) else {
+            return Ok(None);
+        };
+        habit.paused_until = Some(until);
+        let habit = habit.clone();
+
+        tables.end_pauses(id, from);
+        tables.pauses.push(HabitPause {
+            id: Uuid::new_v4(),
+            habit_id: id,
+            starts_on: from,
+            ends_on: until,
+        });
+        Ok(Some(habit))
+    }
+
+    async fn resume(&self, user_id: Uuid, id: Uuid, today: NaiveDate) -> RepoResult<Option<Habit>> {
+        let mut tables = self.lock();
+        let Some(habit) = tables.habit_mut(user_id, id) else {
+            return Ok(None);
+        };
+        habit.paused_until = None;
+        let habit = habit.clone();
+
+        tables.end_pauses(id, today);
+        Ok(Some(habit))
+    }
+
+    async fn user_pauses(&self, user_id: Uuid) -> RepoResult<Vec<HabitPause>> {
+        let tables = self.lock();
+        let habits = tables.user_habit_ids(user_id);
+        Ok(tables
+            .pauses
+            .iter()
+            .filter(|p| habits.contains(&p.habit_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn add_completion(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        value: Option<f64>,
+        same_day: bool,
+    ) -> RepoResult<Option<HabitCompletion>> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(None);
+        }
+        if tables
+            .completions
+            .iter()
+            .any(|c| c.habit_id == habit_id && c.completed_on == on)
+        {
+            return Err(RepoError::Duplicate);
+        }
+
+        let completion = HabitCompletion {
+            id: Uuid::new_v4(),
+            habit_id,
+            completed_on: on,
+            value,
+            same_day,
+        };
+        tables.completions.push(completion.clone());
+        Ok(Some(completion))
+    }
+
+    async fn add_measurement(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        value: f64,
+        same_day: bool,
+    ) -> RepoResult<Option<HabitCompletion>> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(None);
+        }
+        if let Some(completion) = tables
+            .completions
+            .iter_mut()
+            .find(|c| c.habit_id == habit_id && c.completed_on == on)
+        {
+            completion.value = Some(completion.value.unwrap_or(0.0) + value);
+            completion.same_day &= same_day;
+            return Ok(Some(completion.clone()));
+        }
+
+        let completion = HabitCompletion {
+            id: Uuid::new_v4(),
+            habit_id,
+            completed_on: on,
+            value: Some(value),
+            same_day,
+        };
+        tables.completions.push(completion.clone());
+        Ok(Some(completion))
+    }
+
+    async fn completions(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> RepoResult<Vec<HabitCompletion>> {
+        let tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(Vec::new());
+        }
+        let mut completions: Vec<HabitCompletion> = tables
+            .completions
+            .iter()
+            .filter(|c| c.habit_id == habit_id && in_range(c.completed_on, from, to))
+            .cloned()
+            .collect();
+        completions.sort_by_key(|c| c.completed_on);
+        Ok(completions)
+    }
+
+    async fn user_completions(&self, user_id: Uuid) -> RepoResult<Vec<HabitCompletion>> {
+        let tables = self.lock();
+        let habits = tables.user_habit_ids(user_id);
+        Ok(tables
+            .completions
+            .iter()
+            .filter(|c| habits.contains(&c.habit_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_completion(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+    ) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(false);
+        }
+
+        let before = tables.completions.len();
+        tables
+            .completions
+            .retain(|c| !(c.habit_id == habit_id && c.completed_on == on));
+        Ok(tables.completions.len() != before)
+    }
+
+    async fn skips(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> RepoResult<Vec<HabitSkip>> {
+        let tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(Vec::new());
+        }
+        let mut skips: Vec<HabitSkip> = tables
+            .skips
+            .iter()
+            .filter(|s| s.habit_id == habit_id && in_range(s.skipped_on, from, to))
+            .cloned()
+            .collect();
+        skips.sort_by_key(|s| s.skipped_on);
+        Ok(skips)
+    }
+
+    async fn user_skips(&self, user_id: Uuid) -> RepoResult<Vec<HabitSkip>> {
+        let tables = self.lock();
+        let habits = tables.user_habit_ids(user_id);
+        Ok(tables
+            .skips
+            .iter()
+            .filter(|s| habits.contains(&s.habit_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn skip(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        note: Option<String>,
+    ) -> RepoResult<Option<HabitSkip>> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(None);
+        }
+        tables
+            .insert_skip(habit_id, on, SkipKind::Skip, note)
+            .map(Some)
+    }
+
+    async fn freeze(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+    ) -> RepoResult<Option<Freeze>> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(None);
+        }
+        let has_freezes = tables
+            .users
+            .iter()
+            .any(|u| u.id == user_id && u.streak_freezes > 0);
+        if !has_freezes {
+            return Ok(None);
+        }
+
+        // The skip goes in first so that a duplicate leaves the freeze unspent
+        let skip = tables.insert_skip(habit_id, on, SkipKind::Freeze, None)?;
+        let user = tables
+            .users
+            .iter_mut()
+            .find(|u| u.id == user_id)
+            .expect("checked above");
+        user.streak_freezes -= 1;
+        Ok(Some(Freeze {
+            skip,
+            streak_freezes: user.streak_freezes,
+        }))
+    }
+
+    async fn delete_skip(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        max_freezes: i32,
+    ) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(false);
+        }
+        let Some(index) = tables
+            .skips
+            .iter()
+            .position(|s| s.habit_id == habit_id && s.skipped_on == on)
+        else {
+            return Ok(false);
+        };
+
+        let skip = tables.skips.remove(index);
+        if matches!(skip.kind, SkipKind::Freeze)
+            && let Some(user) = tables.users.iter_mut().find(|u| u.id == user_id)
+        {
+            user.streak_freezes = (user.streak_freezes + 1).min(max_freezes);
+        }
+        Ok(true)
+    }
+
+    async fn award_streak_freeze(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        period_start: NaiveDate,
+        max: i32,
+    ) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        if !tables.owns_habit(user_id, habit_id) {
+            return Ok(false);
+        }
+        // PRIMARY KEY (habit_id, period_start)
+        if tables.freeze_awards.contains(&(habit_id, period_start)) {
+            return Ok(false);
+        }
+        tables.freeze_awards.push((habit_id, period_start));
+
+        match tables.users.iter_mut().find(|u| u.id == user_id) {
+            Some(user) if user.streak_freezes < max => {
+                user.streak_freezes += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn vacations(&self, user_id: Uuid) -> RepoResult<Vec<Vacation>> {
+        let mut vacations: Vec<Vacation> = self
+            .lock()
+            .vacations
+            .iter()
+            .filter(|v| v.user_id == user_id)
+            .cloned()
+            .collect();
+        vacations.sort_by_key(|v| v.starts_on);
+        Ok(vacations)
+    }
+
+    async fn create_vacation(
+        &self,
+        user_id: Uuid,
+        vacation: CreateVacation,
+    ) -> RepoResult<Vacation> {
+        let vacation = Vacation {
+            id: Uuid::new_v4(),
+            user_id,
+            starts_on: vacation.starts_on,
+            ends_on: vacation.ends_on,
+            note: vacation.note,
+            created_at: now(),
+        };
+        self.lock().vacations.push(vacation.clone());
+        Ok(vacation)
+    }
+
+    async fn delete_vacation(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        let before = tables.vacations.len();
+        tables
+            .vacations
+            .retain(|v| !(v.id == id && v.user_id == user_id));
+        Ok(tables.vacations.len() != before)
+    }
+}
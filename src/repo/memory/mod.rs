@@ -0,0 +1,66 @@
+// The repositories in process memory, for tests that don't need Postgres. The
+// rules the schema enforces (owners, unique days, one open session, what a
+// delete cascades to or unlinks) are enforced here by hand.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, NaiveDate, SubsecRound, TimeDelta, Utc};
+use uuid::Uuid;
+
+use crate::models::{
+    goal::Goal,
+    habit::{Habit, HabitCompletion, HabitPause, HabitSkip},
+    pomodoro::{Interruption, PomodoroSession, PomodoroSettings},
+    task::{Task, TimeEntry},
+    user::User,
+    vacation::Vacation,
+};
+
+mod goals;
+mod habits;
+mod pomodoro;
+mod tasks;
+mod users;
+
+// One set of tables shared by every repo from Repos::in_memory()
+#[derive(Clone, Default)]
+pub struct Store {
+    tables: Arc<Mutex<Tables>>,
+}
+
+#[derive(Default)]
+struct Tables {
+    users: Vec<User>,
+    tasks: Vec<Task>,
+    time_entries: Vec<TimeEntry>,
+    goals: Vec<Goal>,
+    habits: Vec<Habit>,
+    completions: Vec<HabitCompletion>,
+    skips: Vec<HabitSkip>,
+    pauses: Vec<HabitPause>,
+    // (habit_id, period_start) of every streak period that paid out a freeze
+    freeze_awards: Vec<(Uuid, NaiveDate)>,
+    vacations: Vec<Vacation>,
+    sessions: Vec<PomodoroSession>,
+    interruptions: Vec<Interruption>,
+    settings: Vec<PomodoroSettings>,
+}
+
+impl Store {
+    // Each call holds the lock for the whole operation, like a transaction
+    fn lock(&self) -> MutexGuard<'_, Tables> {
+        self.tables
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Postgres keeps timestamps to the microsecond
+fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(6)
+}
+
+// EXTRACT(EPOCH FROM interval)::int, which rounds to the nearest second
+fn whole_seconds(delta: TimeDelta) -> i64 {
+    (delta.num_microseconds().unwrap_or(i64::MAX) as f64 / 1_000_000.0).round() as i64
+}
@@ -0,0 +1,438 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, SubsecRound, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    models::pomodoro::{
+        FocusSession, Interruption, InterruptionKind, PomodoroSession, PomodoroSettings,
+        SessionStatus, SessionType, UpdatePomodoroSettings,
+    },
+    pomodoro::cycle::{duration_for, next_session_type},
+    repo::{
+        RepoError, RepoResult,
+        memory::{Store, Tables, now, whole_seconds},
+        pomodoro::{Ended, NewSession, PomodoroRepo, Started},
+    },
+};
+
+// The column defaults of pomodoro_settings
+fn default_settings(user_id: Uuid, now: DateTime<Utc>) -> PomodoroSettings {
+    PomodoroSettings {
+        user_id,
+        focus_minutes: 25,
+        short_break_minutes: 5,
+        long_break_minutes: 15,
+        long_break_every: 4,
+        auto_start_next: false,
+        updated_at: now,
+    }
+}
+
+impl Tables {
+    fn settings_for(&mut self, user_id: Uuid, now: DateTime<Utc>) -> &mut PomodoroSettings {
+        let index = match self.settings.iter().position(|s| s.user_id == user_id) {
+            Some(index) => index,
+            None => {
+                self.settings.push(default_settings(user_id, now));
+                self.settings.len() - 1
+            }
+        };
+        &mut self.settings[index]
+    }
+
+    fn owns_session(&self, user_id: Uuid, session_id: Uuid) -> bool {
+        self.sessions
+            .iter()
+            .any(|s| s.id == session_id && s.user_id == user_id)
+    }
+
+    // No task at all counts as owned
+    pub(super) fn owns_task(&self, user_id: Uuid, task_id: Option<Uuid>) -> bool {
+        task_id.is_none_or(|id| {
+            self.tasks
+                .iter()
+                .any(|t| t.id == id && t.user_id == user_id)
+        })
+    }
+
+    fn completed_today(&self, user_id: Uuid, tz: Tz, now: DateTime<Utc>) -> Vec<SessionType> {
+        let today = now.with_timezone(&tz).date_naive();
+        let mut completed: Vec<&PomodoroSession> = self
+            .sessions
+            .iter()
+            .filter(|s| {
+                s.user_id == user_id
+                    && s.status == SessionStatus::Completed
+                    && s.started_at.with_timezone(&tz).date_naive() == today
+            })
+            .collect();
+        completed.sort_by_key(|s| s.started_at);
+        completed
+            .into_iter()
+            .map(|s| s.session_type.clone())
+            .collect()
+    }
+
+    // None when the task isn't the user's; Err(Duplicate) while a session is open
+    fn insert_session(
+        &mut self,
+        user_id: Uuid,
+        tz: Tz,
+        task_id: Option<Uuid>,
+        session_type: Option<SessionType>,
+        duration_minutes: Option<i32>,
+        now: DateTime<Utc>,
+    ) -> RepoResult<Option<PomodoroSession>> {
+        if !self.owns_task(user_id, task_id) {
+            return Ok(None);
+        }
+        if self
+            .sessions
+            .iter()
+            .any(|s| s.user_id == user_id && s.ended_at.is_none())
+        {
+            return Err(RepoError::Duplicate);
+        }
+
+        let completed_today = self.completed_today(user_id, tz, now);
+        let settings = self.settings_for(user_id, now);
+        let session_type =
+            session_type.unwrap_or_else(|| next_session_type(&completed_today, settings));
+        let duration_minutes =
+            duration_minutes.unwrap_or_else(|| duration_for(&session_type, settings));
+
+        let session = PomodoroSession {
+            id: Uuid::new_v4(),
+            user_id,
+            task_id,
+            session_type,
+            duration_minutes,
+            started_at: now,
+            ended_at: None,
+            notes: None,
+            status: SessionStatus::Running,
+            paused_at: None,
+            paused_seconds: 0,
+            auto_closed: false,
+        };
+        self.sessions.push(session.clone());
+        Ok(Some(session))
+    }
+
+    // Ends the user's open session (or the given one, if it's still open)
+    fn end_open_session(
+        &mut self,
+        user_id: Uuid,
+        id: Option<Uuid>,
+        notes: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Option<PomodoroSession> {
+        let session = self.sessions.iter_mut().find(|s| {
+            s.user_id == user_id && id.is_none_or(|id| s.id == id) && s.ended_at.is_none()
+        })?;
+
+        let open_pause = session
+            .paused_at
+            .map_or(0, |paused_at| whole_seconds(now - paused_at));
+        let active = whole_seconds(now - session.started_at)
+            - i64::from(session.paused_seconds)
+            - open_pause;
+        session.status = if active >= i64::from(session.duration_minutes) * 60 {
+            SessionStatus::Completed
+        } else {
+            SessionStatus::Interrupted
+        };
+        session.ended_at = Some(now);
+        session.paused_seconds += open_pause as i32;
+        session.paused_at = None;
+        if notes.is_some() {
+            session.notes = notes;
+        }
+        Some(session.clone())
+    }
+
+    fn focus_sessions(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl Iterator<Item = &PomodoroSession> {
+        self.sessions.iter().filter(move |s| {
+            let day = s.started_at.with_timezone(&tz).date_naive();
+            s.user_id == user_id
+                && s.session_type == SessionType::Focus
+                && s.ended_at.is_some()
+                && from <= day
+                && day <= to
+        })
+    }
+}
+
+#[async_trait]
+impl PomodoroRepo for Store {
+    async fn list(&self, user_id: Uuid) -> RepoResult<Vec<PomodoroSession>> {
+        let mut sessions: Vec<PomodoroSession> = self
+            .lock()
+            .sessions
+            .iter()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+        Ok(sessions)
+    }
+
+    async fn active(&self, user_id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        Ok(self
+            .lock()
+            .sessions
+            .iter()
+            .find(|s| s.user_id == user_id && s.ended_at.is_none())
+            .cloned())
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        Ok(self
+            .lock()
+            .sessions
+            .iter()
+            .find(|s| s.id == id && s.user_id == user_id)
+            .cloned())
+    }
+
+    async fn start(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        session: NewSession,
+    ) -> RepoResult<Option<Started>> {
+        let mut tables = self.lock();
+        let now = now();
+        // Checked up front so that a bad task leaves the open session alone
+        if !tables.owns_task(user_id, session.task_id) {
+            return Ok(None);
+        }
+
+        let replaced = if session.replace {
+            tables.end_open_session(user_id, None, None, now)
+        } else {
+            None
+        };
+        let started = tables.insert_session(
+            user_id,
+            tz,
+            session.task_id,
+            session.session_type,
+            session.duration_minutes,
+            now,
+        )?;
+        Ok(started.map(|session| Started { session, replaced }))
+    }
+
+    async fn end(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        tz: Tz,
+        notes: Option<String>,
+    ) -> RepoResult<Option<Ended>> {
+        let mut tables = self.lock();
+        let now = now();
+
+        if let Some(session) = tables.end_open_session(user_id, Some(id), notes.clone(), now) {
+            let next = if session.status == SessionStatus::Completed
+                && tables.settings_for(user_id, now).auto_start_next
+            {
+                tables.insert_session(user_id, tz, session.task_id, None, None, now)?
+            } else {
+                None
+            };
+            return Ok(Some(Ended {
+                session,
+                just_ended: true,
+                next,
+            }));
+        }
+
+        let Some(session) = tables
+            .sessions
+            .iter_mut()
+            .find(|s| s.id == id && s.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+        if notes.is_some() {
+            session.notes = notes;
+        }
+        Ok(Some(Ended {
+            session: session.clone(),
+            just_ended: false,
+            next: None,
+        }))
+    }
+
+    async fn pause(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        let mut tables = self.lock();
+        let Some(session) = tables
+            .sessions
+            .iter_mut()
+            .find(|s| s.id == id && s.user_id == user_id && s.status == SessionStatus::Running)
+        else {
+            return Ok(None);
+        };
+
+        session.status = SessionStatus::Paused;
+        session.paused_at = Some(now());
+        Ok(Some(session.clone()))
+    }
+
+    async fn resume(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        let mut tables = self.lock();
+        let Some(session) = tables
+            .sessions
+            .iter_mut()
+            .find(|s| s.id == id && s.user_id == user_id && s.status == SessionStatus::Paused)
+        else {
+            return Ok(None);
+        };
+
+        if let Some(paused_at) = session.paused_at.take() {
+            session.paused_seconds += whole_seconds(now() - paused_at) as i32;
+        }
+        session.status = SessionStatus::Running;
+        Ok(Some(session.clone()))
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        let before = tables.sessions.len();
+        tables
+            .sessions
+            .retain(|s| !(s.id == id && s.user_id == user_id));
+        if tables.sessions.len() == before {
+            return Ok(false);
+        }
+
+        // ON DELETE CASCADE for interruptions
+        tables.interruptions.retain(|i| i.session_id != id);
+        Ok(true)
+    }
+
+    async fn interruptions(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepoResult<Vec<Interruption>> {
+        let tables = self.lock();
+        if !tables.owns_session(user_id, session_id) {
+            return Ok(Vec::new());
+        }
+        let mut interruptions: Vec<Interruption> = tables
+            .interruptions
+            .iter()
+            .filter(|i| i.session_id == session_id)
+            .cloned()
+            .collect();
+        interruptions.sort_by_key(|i| i.occurred_at);
+        Ok(interruptions)
+    }
+
+    async fn add_interruption(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        kind: InterruptionKind,
+        occurred_at: DateTime<Utc>,
+        note: Option<String>,
+    ) -> RepoResult<Option<Interruption>> {
+        let mut tables = self.lock();
+        if !tables.owns_session(user_id, session_id) {
+            return Ok(None);
+        }
+        let interruption = Interruption {
+            id: Uuid::new_v4(),
+            session_id,
+            kind,
+            occurred_at: occurred_at.trunc_subsecs(6),
+            note,
+            created_at: now(),
+        };
+        tables.interruptions.push(interruption.clone());
+        Ok(Some(interruption))
+    }
+
+    async fn settings(&self, user_id: Uuid) -> RepoResult<PomodoroSettings> {
+        Ok(self.lock().settings_for(user_id, now()).clone())
+    }
+
+    async fn update_settings(
+        &self,
+        user_id: Uuid,
+        changes: UpdatePomodoroSettings,
+    ) -> RepoResult<PomodoroSettings> {
+        let mut tables = self.lock();
+        let now = now();
+        let settings = tables.settings_for(user_id, now);
+
+        if let Some(minutes) = changes.focus_minutes {
+            settings.focus_minutes = minutes;
+        }
+        if let Some(minutes) = changes.short_break_minutes {
+            settings.short_break_minutes = minutes;
+        }
+        if let Some(minutes) = changes.long_break_minutes {
+            settings.long_break_minutes = minutes;
+        }
+        if let Some(every) = changes.long_break_every {
+            settings.long_break_every = every;
+        }
+        if let Some(auto_start_next) = changes.auto_start_next {
+            settings.auto_start_next = auto_start_next;
+        }
+        settings.updated_at = now;
+        Ok(settings.clone())
+    }
+
+    async fn focus_sessions(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> RepoResult<Vec<FocusSession>> {
+        let tables = self.lock();
+        Ok(tables
+            .focus_sessions(user_id, tz, from, to)
+            .map(|session| {
+                let task = tables.tasks.iter().find(|t| Some(t.id) == session.task_id);
+                FocusSession {
+                    session: session.clone(),
+                    task_title: task.map(|t| t.title.clone()),
+                    task_tags: task.map(|t| t.tags.clone()),
+                }
+            })
+            .collect())
+    }
+
+    async fn focus_interruptions(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> RepoResult<Vec<Interruption>> {
+        let tables = self.lock();
+        let sessions: Vec<Uuid> = tables
+            .focus_sessions(user_id, tz, from, to)
+            .map(|s| s.id)
+            .collect();
+        Ok(tables
+            .interruptions
+            .iter()
+            .filter(|i| sessions.contains(&i.session_id))
+            .cloned()
+            .collect())
+    }
+}
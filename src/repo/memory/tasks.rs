@@ -0,0 +1,302 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        pomodoro::{SessionStatus, SessionType},
+        task::{CreateTask, Priority, Task, TimeEntry, UpdateTask},
+    },
+    repo::{
+        RepoResult,
+        memory::{Store, Tables, now, whole_seconds},
+        tasks::{EffortRow, TaskRepo, UpdatedTask},
+    },
+};
+
+// The column default for pomodoro_settings.focus_minutes
+const DEFAULT_FOCUS_MINUTES: i32 = 25;
+
+impl Tables {
+    fn focus_minutes(&self, user_id: Uuid) -> i32 {
+        self.settings
+            .iter()
+            .find(|s| s.user_id == user_id)
+            .map_or(DEFAULT_FOCUS_MINUTES, |s| s.focus_minutes)
+    }
+
+    fn effort_row(&self, task: &Task) -> EffortRow {
+        let minutes_per_pomodoro = task.pomodoro_minutes.unwrap_or(DEFAULT_FOCUS_MINUTES);
+
+        let mut focus_seconds = 0;
+        let mut completed_pomodoros = 0;
+        for session in self
+            .sessions
+            .iter()
+            .filter(|s| s.task_id == Some(task.id) && s.session_type == SessionType::Focus)
+        {
+            let Some(ended_at) = session.ended_at else {
+                continue;
+            };
+            focus_seconds += (whole_seconds(ended_at - session.started_at)
+                - i64::from(session.paused_seconds))
+            .max(0);
+            if session.status == SessionStatus::Completed {
+                completed_pomodoros += 1;
+            }
+        }
+
+        let manual_minutes = self
+            .time_entries
+            .iter()
+            .filter(|e| e.task_id == task.id)
+            .map(|e| i64::from(e.minutes))
+            .sum();
+
+        EffortRow {
+            task: task.clone(),
+            minutes_per_pomodoro,
+            focus_seconds,
+            completed_pomodoros,
+            manual_minutes,
+        }
+    }
+}
+
+#[async_trait]
+impl TaskRepo for Store {
+    async fn list(&self, user_id: Uuid) -> RepoResult<Vec<Task>> {
+        Ok(self
+            .lock()
+            .tasks
+            .iter()
+            .filter(|t| t.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Task>> {
+        Ok(self
+            .lock()
+            .tasks
+            .iter()
+            .find(|t| t.id == id && t.user_id == user_id)
+            .cloned())
+    }
+
+    async fn effort(&self, user_id: Uuid, task_id: Option<Uuid>) -> RepoResult<Vec<EffortRow>> {
+        let tables = self.lock();
+        let mut rows: Vec<EffortRow> = tables
+            .tasks
+            .iter()
+            .filter(|t| t.user_id == user_id && task_id.is_none_or(|id| t.id == id))
+            .map(|t| tables.effort_row(t))
+            .collect();
+        rows.sort_by_key(|row| row.task.created_at);
+        Ok(rows)
+    }
+
+    async fn open_with_due_date(&self, user_id: Uuid) -> RepoResult<Vec<Task>> {
+        let mut tasks: Vec<Task> = self
+            .lock()
+            .tasks
+            .iter()
+            .filter(|t| t.user_id == user_id && !t.completed && t.due_date.is_some())
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|t| (t.due_date, t.created_at));
+        Ok(tasks)
+    }
+
+    async fn count_completed_after(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        after: NaiveDate,
+    ) -> RepoResult<i64> {
+        Ok(self
+            .lock()
+            .tasks
+            .iter()
+            .filter(|t| {
+                t.user_id == user_id
+                    && t.completed
+                    && t.completed_at
+                        .is_some_and(|at| at.with_timezone(&tz).date_naive() > after)
+            })
+            .count() as i64)
+    }
+
+    async fn create(&self, user_id: Uuid, task: CreateTask) -> RepoResult<Task> {
+        let mut tables = self.lock();
+        let pomodoro_minutes = task
+            .estimated_pomodoros
+            .map(|_| tables.focus_minutes(user_id));
+        let task = Task {
+            id: Uuid::new_v4(),
+            user_id,
+            title: task.title,
+            notes: task.notes,
+            priority: task.priority.unwrap_or(Priority::Medium),
+            due_date: task.due_date,
+            completed: false,
+            completed_at: None,
+            created_at: now(),
+            goal_id: task.goal_id,
+            tags: task.tags.unwrap_or_default(),
+            estimated_pomodoros: task.estimated_pomodoros,
+            estimated_minutes: task.estimated_minutes,
+            pomodoro_minutes,
+        };
+        tables.tasks.push(task.clone());
+        Ok(task)
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateTask,
+    ) -> RepoResult<Option<UpdatedTask>> {
+        let mut tables = self.lock();
+        let focus_minutes = tables.focus_minutes(user_id);
+        let Some(task) = tables
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id && t.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+
+        let completed = changes.completed.unwrap_or(task.completed);
+        let newly_completed = completed && !task.completed;
+        if newly_completed {
+            task.completed_at = Some(now());
+        } else if !completed {
+            task.completed_at = None;
+        }
+        task.completed = completed;
+        if let Some(title) = changes.title {
+            task.title = title;
+        }
+        if let Some(notes) = changes.notes {
+            task.notes = Some(notes);
+        }
+        if let Some(priority) = changes.priority {
+            task.priority = priority;
+        }
+        if let Some(due_date) = changes.due_date {
+            task.due_date = Some(due_date);
+        }
+        if let Some(goal_id) = changes.goal_id {
+            task.goal_id = goal_id;
+        }
+        if let Some(tags) = changes.tags {
+            task.tags = tags;
+        }
+        if let Some(estimate) = changes.estimated_pomodoros {
+            task.estimated_pomodoros = Some(estimate);
+            task.pomodoro_minutes = Some(focus_minutes);
+        }
+        if let Some(estimate) = changes.estimated_minutes {
+            task.estimated_minutes = Some(estimate);
+        }
+
+        Ok(Some(UpdatedTask {
+            task: task.clone(),
+            newly_completed,
+        }))
+    }
+
+    async fn complete(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<UpdatedTask>> {
+        let mut tables = self.lock();
+        let Some(task) = tables
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id && t.user_id == user_id)
+        else {
+            return Ok(None);
+        };
+
+        let newly_completed = !task.completed;
+        if newly_completed {
+            task.completed = true;
+            task.completed_at = Some(now());
+        }
+        Ok(Some(UpdatedTask {
+            task: task.clone(),
+            newly_completed,
+        }))
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        let before = tables.tasks.len();
+        tables
+            .tasks
+            .retain(|t| !(t.id == id && t.user_id == user_id));
+        if tables.tasks.len() == before {
+            return Ok(false);
+        }
+
+        // ON DELETE CASCADE for time entries, ON DELETE SET NULL for sessions
+        tables.time_entries.retain(|e| e.task_id != id);
+        for session in tables.sessions.iter_mut().filter(|s| s.task_id == Some(id)) {
+            session.task_id = None;
+        }
+        Ok(true)
+    }
+
+    async fn time_entries(&self, user_id: Uuid, task_id: Uuid) -> RepoResult<Vec<TimeEntry>> {
+        let tables = self.lock();
+        if !tables.owns_task(user_id, Some(task_id)) {
+            return Ok(Vec::new());
+        }
+        let mut entries: Vec<TimeEntry> = tables
+            .time_entries
+            .iter()
+            .filter(|e| e.task_id == task_id)
+            .cloned()
+            .collect();
+        entries.sort_by_key(|e| (e.spent_on, e.created_at));
+        Ok(entries)
+    }
+
+    async fn add_time_entry(
+        &self,
+        user_id: Uuid,
+        task_id: Uuid,
+        minutes: i32,
+        spent_on: NaiveDate,
+        note: Option<String>,
+    ) -> RepoResult<Option<TimeEntry>> {
+        let mut tables = self.lock();
+        if !tables.owns_task(user_id, Some(task_id)) {
+            return Ok(None);
+        }
+        let entry = TimeEntry {
+            id: Uuid::new_v4(),
+            task_id,
+            minutes,
+            spent_on,
+            note,
+            created_at: now(),
+        };
+        tables.time_entries.push(entry.clone());
+        Ok(Some(entry))
+    }
+
+    async fn delete_time_entry(&self, user_id: Uuid, task_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let mut tables = self.lock();
+        if !tables.owns_task(user_id, Some(task_id)) {
+            return Ok(false);
+        }
+
+        let before = tables.time_entries.len();
+        tables
+            .time_entries
+            .retain(|e| !(e.id == id && e.task_id == task_id));
+        Ok(tables.time_entries.len() != before)
+    }
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    models::user::User,
+    repo::{
+        RepoError, RepoResult,
+        memory::{Store, now},
+        users::{NewUser, UserRepo},
+    },
+};
+
+// The column default for users.streak_freezes
+const INITIAL_STREAK_FREEZES: i32 = 2;
+
+#[async_trait]
+impl UserRepo for Store {
+    async fn create(&self, user: NewUser) -> RepoResult<User> {
+        let mut tables = self.lock();
+        if tables
+            .users
+            .iter()
+            .any(|u| u.username == user.username || u.email == user.email)
+        {
+            return Err(RepoError::Duplicate);
+        }
+
+        let user = User {
+            id: Uuid::new_v4(),
+            username: user.username,
+            email: user.email,
+            password_hash: user.password_hash,
+            created_at: now(),
+            timezone: user.timezone,
+            streak_freezes: INITIAL_STREAK_FREEZES,
+        };
+        tables.users.push(user.clone());
+        Ok(user)
+    }
+
+    async fn find(&self, id: Uuid) -> RepoResult<Option<User>> {
+        Ok(self.lock().users.iter().find(|u| u.id == id).cloned())
+    }
+
+    async fn find_by_email(&self, email: &str) -> RepoResult<Option<User>> {
+        Ok(self.lock().users.iter().find(|u| u.email == email).cloned())
+    }
+
+    async fn update_timezone(
+        &self,
+        id: Uuid,
+        timezone: Option<String>,
+    ) -> RepoResult<Option<User>> {
+        let mut tables = self.lock();
+        let Some(user) = tables.users.iter_mut().find(|u| u.id == id) else {
+            return Ok(None);
+        };
+        if let Some(timezone) = timezone {
+            user.timezone = timezone;
+        }
+        Ok(Some(user.clone()))
+    }
+
+    async fn timezone(&self, id: Uuid) -> RepoResult<Option<String>> {
+        Ok(self
+            .lock()
+            .users
+            .iter()
+            .find(|u| u.id == id)
+            .map(|u| u.timezone.clone()))
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::db::is_unique_violation;
+
+pub mod goals;
+pub mod habits;
+pub mod memory;
+pub mod pomodoro;
+pub mod postgres;
+pub mod tasks;
+pub mod users;
+
+pub use goals::GoalRepo;
+pub use habits::HabitRepo;
+pub use pomodoro::PomodoroRepo;
+pub use tasks::TaskRepo;
+pub use users::UserRepo;
+
+// Storage failures. Rows that don't exist or belong to another user are not
+// errors: lookups return None and deletes false.
+#[derive(Debug, thiserror::Error)]
+pub enum RepoError {
+    // A uniqueness rule was broken, e.g. a second check-in on the same day
+    #[error("duplicate row")]
+    Duplicate,
+    #[error(transparent)]
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for RepoError {
+    fn from(err: sqlx::Error) -> Self {
+        if is_unique_violation(&err) {
+            RepoError::Duplicate
+        } else {
+            RepoError::Database(err)
+        }
+    }
+}
+
+pub type RepoResult<T> = Result<T, RepoError>;
+
+// Every repository the handlers use. Both backends keep the same rules:
+// everything is scoped to its owner, deletes cascade or unlink as the foreign
+// keys in migrations/ do, and the unique indexes surface as Duplicate.
+#[derive(Clone)]
+pub struct Repos {
+    pub users: Arc<dyn UserRepo>,
+    pub tasks: Arc<dyn TaskRepo>,
+    pub goals: Arc<dyn GoalRepo>,
+    pub habits: Arc<dyn HabitRepo>,
+    pub pomodoro: Arc<dyn PomodoroRepo>,
+}
+
+impl Repos {
+    pub fn postgres(pool: PgPool) -> Self {
+        Self {
+            users: Arc::new(postgres::PgUserRepo::new(pool.clone())),
+            tasks: Arc::new(postgres::PgTaskRepo::new(pool.clone())),
+            goals: Arc::new(postgres::PgGoalRepo::new(pool.clone())),
+            habits: Arc::new(postgres::PgHabitRepo::new(pool.clone())),
+            pomodoro: Arc::new(postgres::PgPomodoroRepo::new(pool)),
+        }
+    }
+
+    // All repos share one store, so e.g. deleting a task unlinks its sessions
+    pub fn in_memory() -> Self {
+        let store = memory::Store::default();
+        Self {
+            users: Arc::new(store.clone()),
+            tasks: Arc::new(store.clone()),
+            goals: Arc::new(store.clone()),
+            habits: Arc::new(store.clone()),
+            pomodoro: Arc::new(store),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    models::pomodoro::{
+        FocusSession, Interruption, InterruptionKind, PomodoroSession, PomodoroSettings,
+        SessionType, UpdatePomodoroSettings,
+    },
+    repo::RepoResult,
+};
+
+// A missing session type means the next one in the user's cycle today, a
+// missing duration the user's setting for that type
+pub struct NewSession {
+    pub task_id: Option<Uuid>,
+    pub session_type: Option<SessionType>,
+    pub duration_minutes: Option<i32>,
+    // End the open session instead of refusing to start
+    pub replace: bool,
+}
+
+pub struct Started {
+    pub session: PomodoroSession,
+    pub replaced: Option<PomodoroSession>,
+}
+
+pub struct Ended {
+    pub session: PomodoroSession,
+    // False when the session had already ended and only its notes changed
+    pub just_ended: bool,
+    pub next: Option<PomodoroSession>,
+}
+
+// `tz` is the user's timezone, which decides what "today" is in their cycle
+#[async_trait]
+pub trait PomodoroRepo: Send + Sync {
+    // Newest first
+    async fn list(&self, user_id: Uuid) -> RepoResult<Vec<PomodoroSession>>;
+
+    async fn active(&self, user_id: Uuid) -> RepoResult<Option<PomodoroSession>>;
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>>;
+
+    // Only one session may be open at a time: Err(Duplicate) while one is,
+    // unless `replace` ends it first in the same step. None when the task
+    // doesn't exist or belongs to someone else; nothing is ended then.
+    async fn start(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        session: NewSession,
+    ) -> RepoResult<Option<Started>>;
+
+    // Ends the session if it's still open, folding any open pause into
+    // paused_seconds and classifying it by active time. A completed session
+    // starts the next one when the user has auto_start_next on.
+    async fn end(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        tz: Tz,
+        notes: Option<String>,
+    ) -> RepoResult<Option<Ended>>;
+
+    // None when the session isn't the user's or isn't running
+    async fn pause(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>>;
+
+    // None when the session isn't the user's or isn't paused
+    async fn resume(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>>;
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool>;
+
+    // In order of occurrence; empty when the session isn't the user's
+    async fn interruptions(&self, user_id: Uuid, session_id: Uuid)
+    -> RepoResult<Vec<Interruption>>;
+
+    // None when the session isn't the user's
+    async fn add_interruption(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        kind: InterruptionKind,
+        occurred_at: DateTime<Utc>,
+        note: Option<String>,
+    ) -> RepoResult<Option<Interruption>>;
+
+    // Created with the defaults on first use
+    async fn settings(&self, user_id: Uuid) -> RepoResult<PomodoroSettings>;
+
+    async fn update_settings(
+        &self,
+        user_id: Uuid,
+        changes: UpdatePomodoroSettings,
+    ) -> RepoResult<PomodoroSettings>;
+
+    // Finished focus sessions started on a local date in from..=to
+    async fn focus_sessions(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> RepoResult<Vec<FocusSession>>;
+
+    // The interruptions of those same sessions
+    async fn focus_interruptions(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> RepoResult<Vec<Interruption>>;
+}
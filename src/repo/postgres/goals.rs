@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::goal::{CreateGoal, Goal, UpdateGoal},
+    repo::{
+        RepoResult,
+        goals::{GoalProgress, GoalRepo},
+    },
+};
+
+pub struct PgGoalRepo {
+    pool: PgPool,
+}
+
+impl PgGoalRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl GoalRepo for PgGoalRepo {
+    async fn list_with_progress(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        recent_after: NaiveDate,
+    ) -> RepoResult<Vec<GoalProgress>> {
+        let rows = sqlx::query_as::<_, GoalProgress>(
+            "SELECT g.*,
+                    COUNT(t.id) AS total_tasks,
+                    COUNT(t.id) FILTER (WHERE t.completed) AS completed_tasks,
+                    COUNT(t.id) FILTER (
+                        WHERE t.completed AND (t.completed_at AT TIME ZONE $3)::date > $2
+                    ) AS recent_completions
+             FROM goals g
+             LEFT JOIN tasks t ON t.goal_id = g.id
+             WHERE g.user_id = $1
+             GROUP BY g.id
+             ORDER BY g.created_at",
+        )
+        .bind(user_id)
+        .bind(recent_after)
+        .bind(tz.name())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Goal>> {
+        let goal = sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(goal)
+    }
+
+    async fn create(&self, user_id: Uuid, goal: CreateGoal) -> RepoResult<Goal> {
+        let goal = sqlx::query_as::<_, Goal>(
+            "INSERT INTO goals (user_id, title, description, deadline, status) \
+             VALUES ($1, $2, $3, $4, 'active') \
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(goal.title)
+        .bind(goal.description)
+        .bind(goal.deadline)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(goal)
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateGoal,
+    ) -> RepoResult<Option<Goal>> {
+        let goal = sqlx::query_as::<_, Goal>(
+            "UPDATE goals
+             SET title = COALESCE($3, title),
+                 description = COALESCE($4, description),
+                 deadline = COALESCE($5, deadline),
+                 status = COALESCE($6, status)
+             WHERE id = $1 AND user_id = $2
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(changes.title)
+        .bind(changes.description)
+        .bind(changes.deadline)
+        .bind(changes.status)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(goal)
+    }
+
+    async fn complete(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Goal>> {
+        let goal = sqlx::query_as::<_, Goal>(
+            "UPDATE goals
+             SET status = 'completed'
+             WHERE id = $1 AND user_id = $2
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(goal)
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let deleted = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM goals WHERE id = $1 AND user_id = $2 RETURNING id",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(deleted.is_some())
+    }
+}
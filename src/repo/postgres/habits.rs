@@ -0,0 +1,583 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        habit::{
+            CreateHabit, Frequency, Habit, HabitCompletion, HabitPause, HabitSkip, SkipKind,
+            UpdateHabit,
+        },
+        vacation::{CreateVacation, Vacation},
+    },
+    repo::{
+        RepoResult,
+        habits::{Freeze, HabitRepo},
+    },
+};
+
+pub struct PgHabitRepo {
+    pool: PgPool,
+}
+
+impl PgHabitRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HabitRepo for PgHabitRepo {
+    async fn list(&self, user_id: Uuid, include_archived: bool) -> RepoResult<Vec<Habit>> {
+        let habits = sqlx::query_as::<_, Habit>(
+            "SELECT * FROM habits
+             WHERE user_id = $1 AND deleted_at IS NULL AND (archived_at IS NULL OR $2)",
+        )
+        .bind(user_id)
+        .bind(include_archived)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(habits)
+    }
+
+    async fn active(&self, user_id: Uuid, today: NaiveDate) -> RepoResult<Vec<Habit>> {
+        let habits = sqlx::query_as::<_, Habit>(
+            "SELECT * FROM habits
+             WHERE user_id = $1
+               AND deleted_at IS NULL
+               AND archived_at IS NULL
+               AND (paused_until IS NULL OR paused_until < $2)",
+        )
+        .bind(user_id)
+        .bind(today)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(habits)
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Habit>> {
+        let habit = sqlx::query_as::<_, Habit>(
+            "SELECT * FROM habits WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(habit)
+    }
+
+    async fn create(&self, user_id: Uuid, habit: CreateHabit) -> RepoResult<Habit> {
+        let habit = sqlx::query_as::<_, Habit>(
+            "INSERT INTO habits \
+             (user_id, name, frequency, weekdays, target_count, interval_days, unit, daily_target) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(habit.name)
+        .bind(habit.frequency.unwrap_or(Frequency::Daily))
+        .bind(habit.weekdays)
+        .bind(habit.target_count)
+        .bind(habit.interval_days)
+        .bind(habit.unit)
+        .bind(habit.daily_target)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(habit)
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateHabit,
+    ) -> RepoResult<Option<Habit>> {
+        let habit = sqlx::query_as::<_, Habit>(
+            "UPDATE habits
+             SET name = COALESCE($3, name),
+                 frequency = COALESCE($4, frequency),
+                 weekdays = COALESCE($5, weekdays),
+                 target_count = COALESCE($6, target_count),
+                 interval_days = COALESCE($7, interval_days),
+                 unit = COALESCE($8, unit),
+                 daily_target = COALESCE($9, daily_target)
+             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(changes.name)
+        .bind(changes.frequency)
+        .bind(changes.weekdays)
+        .bind(changes.target_count)
+        .bind(changes.interval_days)
+        .bind(changes.unit)
+        .bind(changes.daily_target)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(habit)
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        // Kept until purge-deleted removes it along with its history
+        let deleted = sqlx::query_scalar::<_, Uuid>(
+            "UPDATE habits SET deleted_at = NOW()
+             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+             RETURNING id",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(deleted.is_some())
+    }
+
+    async fn set_archived(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        archived: bool,
+    ) -> RepoResult<Option<Habit>> {
+        let habit = sqlx::query_as::<_, Habit>(
+            "UPDATE habits
+             SET archived_at = CASE WHEN $3 THEN COALESCE(archived_at, NOW()) END
+             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(archived)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(habit)
+    }
+
+    async fn pause(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> RepoResult<Option<Habit>> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(habit) = set_paused_until(&mut tx, user_id, id, Some(until)).await? else {
+            return Ok(None);
+        };
+        end_pauses(&mut tx, id, from).await?;
+        sqlx::query("INSERT INTO habit_pauses (habit_id, starts_on, ends_on) VALUES ($1, $2, $3)")
+            .bind(id)
+            .bind(from)
+            .bind(until)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(Some(habit))
+    }
+
+    async fn resume(&self, user_id: Uuid, id: Uuid, today: NaiveDate) -> RepoResult<Option<Habit>> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(habit) = set_paused_until(&mut tx, user_id, id, None).await? else {
+            return Ok(None);
+        };
+        end_pauses(&mut tx, id, today).await?;
+
+        tx.commit().await?;
+        Ok(Some(habit))
+    }
+
+    async fn user_pauses(&self, user_id: Uuid) -> RepoResult<Vec<HabitPause>> {
+        let pauses = sqlx::query_as::<_, HabitPause>(
+            "SELECT hp.*
+             FROM habit_pauses hp
+             JOIN habits h ON h.id = hp.habit_id
+             WHERE h.user_id = $1 AND h.deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(pauses)
+    }
+
+    async fn add_completion(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        value: Option<f64>,
+        same_day: bool,
+    ) -> RepoResult<Option<HabitCompletion>> {
+        let completion = sqlx::query_as::<_, HabitCompletion>(
+            "INSERT INTO habit_completions (habit_id, completed_on, value, same_day)
+             SELECT id, $2, $3, $4
+             FROM habits
+             WHERE id = $1 AND user_id = $5 AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(habit_id)
+        .bind(on)
+        .bind(value)
+        .bind(same_day)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(completion)
+    }
+
+    async fn add_measurement(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        value: f64,
+        same_day: bool,
+    ) -> RepoResult<Option<HabitCompletion>> {
+        let completion = sqlx::query_as::<_, HabitCompletion>(
+            "INSERT INTO habit_completions (habit_id, completed_on, value, same_day)
+             SELECT id, $2, $3, $4
+             FROM habits
+             WHERE id = $1 AND user_id = $5 AND deleted_at IS NULL
+             ON CONFLICT (habit_id, completed_on)
+             DO UPDATE SET value = COALESCE(habit_completions.value, 0) + EXCLUDED.value,
+                           same_day = habit_completions.same_day AND EXCLUDED.same_day
+             RETURNING *",
+        )
+        .bind(habit_id)
+        .bind(on)
+        .bind(value)
+        .bind(same_day)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(completion)
+    }
+
+    async fn completions(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> RepoResult<Vec<HabitCompletion>> {
+        let completions = sqlx::query_as::<_, HabitCompletion>(
+            "SELECT hc.*
+             FROM habit_completions hc
+             JOIN habits h ON h.id = hc.habit_id
+             WHERE hc.habit_id = $1
+               AND h.user_id = $4
+               AND h.deleted_at IS NULL
+               AND ($2::date IS NULL OR hc.completed_on >= $2)
+               AND ($3::date IS NULL OR hc.completed_on <= $3)
+             ORDER BY hc.completed_on",
+        )
+        .bind(habit_id)
+        .bind(from)
+        .bind(to)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(completions)
+    }
+
+    async fn user_completions(&self, user_id: Uuid) -> RepoResult<Vec<HabitCompletion>> {
+        let completions = sqlx::query_as::<_, HabitCompletion>(
+            "SELECT hc.*
+             FROM habit_completions hc
+             JOIN habits h ON h.id = hc.habit_id
+             WHERE h.user_id = $1 AND h.deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(completions)
+    }
+
+    async fn delete_completion(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+    ) -> RepoResult<bool> {
+        let deleted = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM habit_completions hc
+             USING habits h
+             WHERE hc.habit_id = h.id AND h.id = $1 AND h.user_id = $2 AND h.deleted_at IS NULL
+               AND hc.completed_on = $3
+             RETURNING hc.id",
+        )
+        .bind(habit_id)
+        .bind(user_id)
+        .bind(on)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(deleted.is_some())
+    }
+
+    async fn skips(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> RepoResult<Vec<HabitSkip>> {
+        let skips = sqlx::query_as::<_, HabitSkip>(
+            "SELECT hs.*
+             FROM habit_skips hs
+             JOIN habits h ON h.id = hs.habit_id
+             WHERE hs.habit_id = $1
+               AND h.user_id = $4
+               AND h.deleted_at IS NULL
+               AND ($2::date IS NULL OR hs.skipped_on >= $2)
+               AND ($3::date IS NULL OR hs.skipped_on <= $3)
+             ORDER BY hs.skipped_on",
+        )
+        .bind(habit_id)
+        .bind(from)
+        .bind(to)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(skips)
+    }
+
+    async fn user_skips(&self, user_id: Uuid) -> RepoResult<Vec<HabitSkip>> {
+        let skips = sqlx::query_as::<_, HabitSkip>(
+            "SELECT hs.*
+             FROM habit_skips hs
+             JOIN habits h ON h.id = hs.habit_id
+             WHERE h.user_id = $1 AND h.deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(skips)
+    }
+
+    async fn skip(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        note: Option<String>,
+    ) -> RepoResult<Option<HabitSkip>> {
+        let skip = sqlx::query_as::<_, HabitSkip>(
+            "INSERT INTO habit_skips (habit_id, skipped_on, kind, note)
+             SELECT id, $2, 'skip', $3
+             FROM habits
+             WHERE id = $1 AND user_id = $4 AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(habit_id)
+        .bind(on)
+        .bind(note)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(skip)
+    }
+
+    async fn freeze(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+    ) -> RepoResult<Option<Freeze>> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(remaining) = sqlx::query_scalar::<_, i32>(
+            "UPDATE users SET streak_freezes = streak_freezes - 1
+             WHERE id = $1
+               AND streak_freezes > 0
+               AND EXISTS (
+                   SELECT 1 FROM habits
+                   WHERE id = $2 AND user_id = $1 AND deleted_at IS NULL
+               )
+             RETURNING streak_freezes",
+        )
+        .bind(user_id)
+        .bind(habit_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        // Returning early drops the transaction, which rolls the spent freeze back
+        let skip = sqlx::query_as::<_, HabitSkip>(
+            "INSERT INTO habit_skips (habit_id, skipped_on, kind)
+             VALUES ($1, $2, 'freeze')
+             RETURNING *",
+        )
+        .bind(habit_id)
+        .bind(on)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(Freeze {
+            skip,
+            streak_freezes: remaining,
+        }))
+    }
+
+    async fn delete_skip(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        on: NaiveDate,
+        max_freezes: i32,
+    ) -> RepoResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(kind) = sqlx::query_scalar::<_, SkipKind>(
+            "DELETE FROM habit_skips hs
+             USING habits h
+             WHERE hs.habit_id = h.id AND h.id = $1 AND h.user_id = $2 AND h.deleted_at IS NULL
+               AND hs.skipped_on = $3
+             RETURNING hs.kind",
+        )
+        .bind(habit_id)
+        .bind(user_id)
+        .bind(on)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(false);
+        };
+
+        if matches!(kind, SkipKind::Freeze) {
+            sqlx::query(
+                "UPDATE users SET streak_freezes = LEAST(streak_freezes + 1, $2) WHERE id = $1",
+            )
+            .bind(user_id)
+            .bind(max_freezes)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn award_streak_freeze(
+        &self,
+        user_id: Uuid,
+        habit_id: Uuid,
+        period_start: NaiveDate,
+        max: i32,
+    ) -> RepoResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let recorded = sqlx::query(
+            "INSERT INTO streak_freeze_awards (habit_id, period_start)
+             SELECT id, $2
+             FROM habits
+             WHERE id = $1 AND user_id = $3 AND deleted_at IS NULL
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(habit_id)
+        .bind(period_start)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        if recorded.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let awarded = sqlx::query_scalar::<_, Uuid>(
+            "UPDATE users SET streak_freezes = streak_freezes + 1
+             WHERE id = $1 AND streak_freezes < $2
+             RETURNING id",
+        )
+        .bind(user_id)
+        .bind(max)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(awarded.is_some())
+    }
+
+    async fn vacations(&self, user_id: Uuid) -> RepoResult<Vec<Vacation>> {
+        let vacations = sqlx::query_as::<_, Vacation>(
+            "SELECT * FROM vacations WHERE user_id = $1 ORDER BY starts_on",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(vacations)
+    }
+
+    async fn create_vacation(
+        &self,
+        user_id: Uuid,
+        vacation: CreateVacation,
+    ) -> RepoResult<Vacation> {
+        let vacation = sqlx::query_as::<_, Vacation>(
+            "INSERT INTO vacations (user_id, starts_on, ends_on, note) \
+             VALUES ($1, $2, $3, $4) \
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(vacation.starts_on)
+        .bind(vacation.ends_on)
+        .bind(vacation.note)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(vacation)
+    }
+
+    async fn delete_vacation(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let deleted = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM vacations WHERE id = $1 AND user_id = $2 RETURNING id",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(deleted.is_some())
+    }
+}
+
+async fn set_paused_until(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    id: Uuid,
+    until: Option<NaiveDate>,
+) -> Result<Option<Habit>, sqlx::Error> {
+    sqlx::query_as::<_, Habit>(
+        "UPDATE habits
+         SET paused_until = $3
+         WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(until)
+    .fetch_optional(conn)
+    .await
+}
+
+// Cuts the habit's pauses short so that none covers `from` or later
+async fn end_pauses(
+    conn: &mut PgConnection,
+    habit_id: Uuid,
+    from: NaiveDate,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM habit_pauses WHERE habit_id = $1 AND starts_on >= $2")
+        .bind(habit_id)
+        .bind(from)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        "UPDATE habit_pauses SET ends_on = $2::date - 1 WHERE habit_id = $1 AND ends_on >= $2",
+    )
+    .bind(habit_id)
+    .bind(from)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+// The repositories on Postgres; the schema is in migrations/
+
+mod goals;
+mod habits;
+mod pomodoro;
+mod tasks;
+mod users;
+
+pub use goals::PgGoalRepo;
+pub use habits::PgHabitRepo;
+pub use pomodoro::PgPomodoroRepo;
+pub use tasks::PgTaskRepo;
+pub use users::PgUserRepo;
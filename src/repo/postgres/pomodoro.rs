@@ -0,0 +1,429 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::pomodoro::{
+        FocusSession, Interruption, InterruptionKind, PomodoroSession, PomodoroSettings,
+        SessionStatus, SessionType, UpdatePomodoroSettings,
+    },
+    pomodoro::cycle::{duration_for, next_session_type},
+    repo::{
+        RepoResult,
+        pomodoro::{Ended, NewSession, PomodoroRepo, Started},
+    },
+};
+
+pub struct PgPomodoroRepo {
+    pool: PgPool,
+}
+
+impl PgPomodoroRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+// Creates the user's settings row with the defaults on first use
+async fn load_settings<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: Uuid,
+) -> Result<PomodoroSettings, sqlx::Error> {
+    sqlx::query_as::<_, PomodoroSettings>(
+        "INSERT INTO pomodoro_settings (user_id) VALUES ($1)
+         ON CONFLICT (user_id) DO UPDATE SET user_id = EXCLUDED.user_id
+         RETURNING *",
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await
+}
+
+// Types of the sessions completed so far on the user's current calendar day, oldest first
+async fn completed_today<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: Uuid,
+    tz: Tz,
+) -> Result<Vec<SessionType>, sqlx::Error> {
+    sqlx::query_scalar::<_, SessionType>(
+        "SELECT session_type FROM pomodoro_sessions
+         WHERE user_id = $1
+           AND status = 'completed'
+           AND (started_at AT TIME ZONE $2)::date = (NOW() AT TIME ZONE $2)::date
+         ORDER BY started_at",
+    )
+    .bind(user_id)
+    .bind(tz.name())
+    .fetch_all(executor)
+    .await
+}
+
+// Ok(None) means the task doesn't exist or belongs to someone else
+async fn insert_session(
+    tx: &mut PgConnection,
+    user_id: Uuid,
+    tz: Tz,
+    task_id: Option<Uuid>,
+    session_type: Option<SessionType>,
+    duration_minutes: Option<i32>,
+) -> Result<Option<PomodoroSession>, sqlx::Error> {
+    let settings = load_settings(&mut *tx, user_id).await?;
+    let session_type = match session_type {
+        Some(session_type) => session_type,
+        None => next_session_type(&completed_today(&mut *tx, user_id, tz).await?, &settings),
+    };
+    let duration_minutes =
+        duration_minutes.unwrap_or_else(|| duration_for(&session_type, &settings));
+
+    sqlx::query_as::<_, PomodoroSession>(
+        "INSERT INTO pomodoro_sessions (user_id, task_id, session_type, duration_minutes)
+         SELECT
+             $1,
+             CASE WHEN $2::uuid IS NULL THEN NULL ELSE t.id END,
+             $3,
+             $4
+         FROM (SELECT 1) seed
+         LEFT JOIN tasks t ON t.id = $2 AND t.user_id = $1
+         WHERE $2::uuid IS NULL OR t.id IS NOT NULL
+         RETURNING *",
+    )
+    .bind(user_id)
+    .bind(task_id)
+    .bind(session_type)
+    .bind(duration_minutes)
+    .fetch_optional(&mut *tx)
+    .await
+}
+
+// Ends the user's open session (or the given one, if it's still open)
+async fn end_open_session<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: Uuid,
+    id: Option<Uuid>,
+    notes: Option<&str>,
+) -> Result<Option<PomodoroSession>, sqlx::Error> {
+    sqlx::query_as::<_, PomodoroSession>(
+        "UPDATE pomodoro_sessions
+         SET ended_at = NOW(),
+             paused_seconds = paused_seconds
+                 + COALESCE(EXTRACT(EPOCH FROM NOW() - paused_at)::int, 0),
+             paused_at = NULL,
+             status = CASE
+                 WHEN EXTRACT(EPOCH FROM NOW() - started_at)::int
+                      - paused_seconds
+                      - COALESCE(EXTRACT(EPOCH FROM NOW() - paused_at)::int, 0)
+                      >= duration_minutes * 60
+                 THEN $3
+                 ELSE $4
+             END,
+             notes = COALESCE($5, notes)
+         WHERE user_id = $1 AND ($2::uuid IS NULL OR id = $2) AND ended_at IS NULL
+         RETURNING *",
+    )
+    .bind(user_id)
+    .bind(id)
+    .bind(SessionStatus::Completed)
+    .bind(SessionStatus::Interrupted)
+    .bind(notes)
+    .fetch_optional(executor)
+    .await
+}
+
+#[async_trait]
+impl PomodoroRepo for PgPomodoroRepo {
+    async fn list(&self, user_id: Uuid) -> RepoResult<Vec<PomodoroSession>> {
+        let sessions = sqlx::query_as::<_, PomodoroSession>(
+            "SELECT * FROM pomodoro_sessions WHERE user_id = $1 ORDER BY started_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(sessions)
+    }
+
+    async fn active(&self, user_id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        let session = sqlx::query_as::<_, PomodoroSession>(
+            "SELECT * FROM pomodoro_sessions WHERE user_id = $1 AND ended_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(session)
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        let session = sqlx::query_as::<_, PomodoroSession>(
+            "SELECT * FROM pomodoro_sessions WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(session)
+    }
+
+    // The one-open-session rule is a partial unique index, so a lost race
+    // surfaces as Duplicate too
+    async fn start(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        session: NewSession,
+    ) -> RepoResult<Option<Started>> {
+        let mut tx = self.pool.begin().await?;
+
+        let replaced = if session.replace {
+            end_open_session(&mut *tx, user_id, None, None).await?
+        } else {
+            None
+        };
+
+        // Returning early drops the transaction, which undoes the replacement
+        let Some(started) = insert_session(
+            &mut tx,
+            user_id,
+            tz,
+            session.task_id,
+            session.session_type,
+            session.duration_minutes,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        tx.commit().await?;
+        Ok(Some(Started {
+            session: started,
+            replaced,
+        }))
+    }
+
+    async fn end(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        tz: Tz,
+        notes: Option<String>,
+    ) -> RepoResult<Option<Ended>> {
+        let mut tx = self.pool.begin().await?;
+
+        let ended = end_open_session(&mut *tx, user_id, Some(id), notes.as_deref()).await?;
+
+        let ended = match ended {
+            Some(session) if session.status == SessionStatus::Completed => {
+                let next = if load_settings(&mut *tx, user_id).await?.auto_start_next {
+                    insert_session(&mut tx, user_id, tz, session.task_id, None, None).await?
+                } else {
+                    None
+                };
+                Ended {
+                    session,
+                    just_ended: true,
+                    next,
+                }
+            }
+            Some(session) => Ended {
+                session,
+                just_ended: true,
+                next: None,
+            },
+            None => {
+                let Some(session) = sqlx::query_as::<_, PomodoroSession>(
+                    "UPDATE pomodoro_sessions
+                     SET notes = COALESCE($3, notes)
+                     WHERE id = $1 AND user_id = $2
+                     RETURNING *",
+                )
+                .bind(id)
+                .bind(user_id)
+                .bind(notes)
+                .fetch_optional(&mut *tx)
+                .await?
+                else {
+                    return Ok(None);
+                };
+                Ended {
+                    session,
+                    just_ended: false,
+                    next: None,
+                }
+            }
+        };
+
+        tx.commit().await?;
+        Ok(Some(ended))
+    }
+
+    async fn pause(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        let session = sqlx::query_as::<_, PomodoroSession>(
+            "UPDATE pomodoro_sessions
+             SET status = 'paused', paused_at = NOW()
+             WHERE id = $1 AND user_id = $2 AND status = 'running'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(session)
+    }
+
+    async fn resume(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<PomodoroSession>> {
+        let session = sqlx::query_as::<_, PomodoroSession>(
+            "UPDATE pomodoro_sessions
+             SET status = 'running',
+                 paused_seconds = paused_seconds + EXTRACT(EPOCH FROM NOW() - paused_at)::int,
+                 paused_at = NULL
+             WHERE id = $1 AND user_id = $2 AND status = 'paused'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(session)
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let deleted = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM pomodoro_sessions WHERE id = $1 AND user_id = $2 RETURNING id",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(deleted.is_some())
+    }
+
+    async fn interruptions(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepoResult<Vec<Interruption>> {
+        let interruptions = sqlx::query_as::<_, Interruption>(
+            "SELECT i.*
+             FROM pomodoro_interruptions i
+             JOIN pomodoro_sessions s ON s.id = i.session_id
+             WHERE i.session_id = $1 AND s.user_id = $2
+             ORDER BY i.occurred_at",
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(interruptions)
+    }
+
+    async fn add_interruption(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        kind: InterruptionKind,
+        occurred_at: DateTime<Utc>,
+        note: Option<String>,
+    ) -> RepoResult<Option<Interruption>> {
+        let interruption = sqlx::query_as::<_, Interruption>(
+            "INSERT INTO pomodoro_interruptions (session_id, kind, occurred_at, note)
+             SELECT id, $2, $3, $4
+             FROM pomodoro_sessions
+             WHERE id = $1 AND user_id = $5
+             RETURNING *",
+        )
+        .bind(session_id)
+        .bind(kind)
+        .bind(occurred_at)
+        .bind(note)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(interruption)
+    }
+
+    async fn settings(&self, user_id: Uuid) -> RepoResult<PomodoroSettings> {
+        Ok(load_settings(&self.pool, user_id).await?)
+    }
+
+    async fn update_settings(
+        &self,
+        user_id: Uuid,
+        changes: UpdatePomodoroSettings,
+    ) -> RepoResult<PomodoroSettings> {
+        let settings = sqlx::query_as::<_, PomodoroSettings>(
+            "INSERT INTO pomodoro_settings (
+                 user_id, focus_minutes, short_break_minutes, long_break_minutes,
+                 long_break_every, auto_start_next
+             )
+             VALUES ($1, COALESCE($2, 25), COALESCE($3, 5), COALESCE($4, 15), COALESCE($5, 4), COALESCE($6, FALSE))
+             ON CONFLICT (user_id) DO UPDATE SET
+                 focus_minutes = COALESCE($2, pomodoro_settings.focus_minutes),
+                 short_break_minutes = COALESCE($3, pomodoro_settings.short_break_minutes),
+                 long_break_minutes = COALESCE($4, pomodoro_settings.long_break_minutes),
+                 long_break_every = COALESCE($5, pomodoro_settings.long_break_every),
+                 auto_start_next = COALESCE($6, pomodoro_settings.auto_start_next),
+                 updated_at = NOW()
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(changes.focus_minutes)
+        .bind(changes.short_break_minutes)
+        .bind(changes.long_break_minutes)
+        .bind(changes.long_break_every)
+        .bind(changes.auto_start_next)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(settings)
+    }
+
+    async fn focus_sessions(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> RepoResult<Vec<FocusSession>> {
+        let sessions = sqlx::query_as::<_, FocusSession>(
+            "SELECT ps.*, t.title AS task_title, t.tags AS task_tags
+             FROM pomodoro_sessions ps
+             LEFT JOIN tasks t ON t.id = ps.task_id
+             WHERE ps.user_id = $1
+               AND ps.session_type = 'focus'
+               AND ps.ended_at IS NOT NULL
+               AND (ps.started_at AT TIME ZONE $2)::date BETWEEN $3 AND $4",
+        )
+        .bind(user_id)
+        .bind(tz.name())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(sessions)
+    }
+
+    async fn focus_interruptions(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> RepoResult<Vec<Interruption>> {
+        let interruptions = sqlx::query_as::<_, Interruption>(
+            "SELECT pi.*
+             FROM pomodoro_interruptions pi
+             JOIN pomodoro_sessions ps ON ps.id = pi.session_id
+             WHERE ps.user_id = $1
+               AND ps.session_type = 'focus'
+               AND ps.ended_at IS NOT NULL
+               AND (ps.started_at AT TIME ZONE $2)::date BETWEEN $3 AND $4",
+        )
+        .bind(user_id)
+        .bind(tz.name())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(interruptions)
+    }
+}
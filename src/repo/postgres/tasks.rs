@@ -0,0 +1,263 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::task::{CreateTask, Task, TimeEntry, UpdateTask},
+    repo::{
+        RepoResult,
+        tasks::{EffortRow, TaskRepo, UpdatedTask},
+    },
+};
+
+pub struct PgTaskRepo {
+    pool: PgPool,
+}
+
+impl PgTaskRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TaskRepo for PgTaskRepo {
+    async fn list(&self, user_id: Uuid) -> RepoResult<Vec<Task>> {
+        let tasks = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(tasks)
+    }
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Task>> {
+        let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(task)
+    }
+
+    async fn effort(&self, user_id: Uuid, task_id: Option<Uuid>) -> RepoResult<Vec<EffortRow>> {
+        let rows = sqlx::query_as::<_, EffortRow>(
+            "SELECT t.*,
+                    COALESCE(t.pomodoro_minutes, 25) AS minutes_per_pomodoro,
+                    COALESCE(focus.seconds, 0) AS focus_seconds,
+                    COALESCE(focus.completed, 0) AS completed_pomodoros,
+                    COALESCE(manual.minutes, 0) AS manual_minutes
+             FROM tasks t
+             LEFT JOIN LATERAL (
+                 SELECT SUM(GREATEST(
+                            EXTRACT(EPOCH FROM ps.ended_at - ps.started_at)::bigint - ps.paused_seconds,
+                            0
+                        ))::bigint AS seconds,
+                        COUNT(*) FILTER (WHERE ps.status = 'completed') AS completed
+                 FROM pomodoro_sessions ps
+                 WHERE ps.task_id = t.id AND ps.session_type = 'focus' AND ps.ended_at IS NOT NULL
+             ) focus ON TRUE
+             LEFT JOIN LATERAL (
+                 SELECT SUM(e.minutes)::bigint AS minutes
+                 FROM task_time_entries e
+                 WHERE e.task_id = t.id
+             ) manual ON TRUE
+             WHERE t.user_id = $1 AND ($2::uuid IS NULL OR t.id = $2)
+             ORDER BY t.created_at",
+        )
+        .bind(user_id)
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn open_with_due_date(&self, user_id: Uuid) -> RepoResult<Vec<Task>> {
+        let tasks = sqlx::query_as::<_, Task>(
+            "SELECT * FROM tasks
+             WHERE user_id = $1 AND completed = FALSE AND due_date IS NOT NULL
+             ORDER BY due_date, created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tasks)
+    }
+
+    async fn count_completed_after(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        after: NaiveDate,
+    ) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM tasks
+             WHERE user_id = $1
+               AND completed = TRUE
+               AND (completed_at AT TIME ZONE $3)::date > $2",
+        )
+        .bind(user_id)
+        .bind(after)
+        .bind(tz.name())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, user_id: Uuid, task: CreateTask) -> RepoResult<Task> {
+        let task = sqlx::query_as::<_, Task>(
+            "INSERT INTO tasks (
+                 user_id, title, notes, priority, due_date, goal_id, tags,
+                 estimated_pomodoros, estimated_minutes, pomodoro_minutes
+             )
+             VALUES (
+                 $1, $2, $3, COALESCE($4, 'medium'), $5, $6, $7, $8, $9,
+                 CASE WHEN $8 IS NOT NULL THEN COALESCE(
+                     (SELECT focus_minutes FROM pomodoro_settings WHERE user_id = $1),
+                     25
+                 ) END
+             )
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(task.title)
+        .bind(task.notes)
+        .bind(task.priority)
+        .bind(task.due_date)
+        .bind(task.goal_id)
+        .bind(task.tags.unwrap_or_default())
+        .bind(task.estimated_pomodoros)
+        .bind(task.estimated_minutes)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(task)
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateTask,
+    ) -> RepoResult<Option<UpdatedTask>> {
+        let updated = sqlx::query_as::<_, UpdatedTask>(
+            "UPDATE tasks
+             SET title = COALESCE($3, title),
+                 notes = COALESCE($4, notes),
+                 priority = COALESCE($5, priority),
+                 due_date = COALESCE($6, due_date),
+                 completed = COALESCE($7, completed),
+                 goal_id = CASE WHEN $8 THEN $9 ELSE goal_id END,
+                 tags = COALESCE($10, tags),
+                 estimated_pomodoros = COALESCE($11, estimated_pomodoros),
+                 pomodoro_minutes = CASE WHEN $11 IS NULL THEN pomodoro_minutes ELSE COALESCE(
+                     (SELECT focus_minutes FROM pomodoro_settings WHERE user_id = $2),
+                     25
+                 ) END,
+                 estimated_minutes = COALESCE($12, estimated_minutes),
+                 completed_at = CASE
+                     WHEN COALESCE($7, completed) = TRUE AND completed = FALSE THEN NOW()
+                     WHEN COALESCE($7, completed) = FALSE THEN NULL
+                     ELSE completed_at
+                 END
+             WHERE id = $1 AND user_id = $2
+             RETURNING *, COALESCE(completed_at = NOW(), FALSE) AS newly_completed",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(changes.title)
+        .bind(changes.notes)
+        .bind(changes.priority)
+        .bind(changes.due_date)
+        .bind(changes.completed)
+        .bind(changes.goal_id.is_some())
+        .bind(changes.goal_id.flatten())
+        .bind(changes.tags)
+        .bind(changes.estimated_pomodoros)
+        .bind(changes.estimated_minutes)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(updated)
+    }
+
+    async fn complete(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<UpdatedTask>> {
+        let updated = sqlx::query_as::<_, UpdatedTask>(
+            "UPDATE tasks
+             SET completed = TRUE,
+                 completed_at = CASE WHEN completed THEN completed_at ELSE NOW() END
+             WHERE id = $1 AND user_id = $2
+             RETURNING *, COALESCE(completed_at = NOW(), FALSE) AS newly_completed",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(updated)
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let deleted = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM tasks WHERE id = $1 AND user_id = $2 RETURNING id",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(deleted.is_some())
+    }
+
+    async fn time_entries(&self, user_id: Uuid, task_id: Uuid) -> RepoResult<Vec<TimeEntry>> {
+        let entries = sqlx::query_as::<_, TimeEntry>(
+            "SELECT e.*
+             FROM task_time_entries e
+             JOIN tasks t ON t.id = e.task_id
+             WHERE e.task_id = $1 AND t.user_id = $2
+             ORDER BY e.spent_on, e.created_at",
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    async fn add_time_entry(
+        &self,
+        user_id: Uuid,
+        task_id: Uuid,
+        minutes: i32,
+        spent_on: NaiveDate,
+        note: Option<String>,
+    ) -> RepoResult<Option<TimeEntry>> {
+        let entry = sqlx::query_as::<_, TimeEntry>(
+            "INSERT INTO task_time_entries (task_id, minutes, spent_on, note)
+             SELECT id, $2, $3, $4
+             FROM tasks
+             WHERE id = $1 AND user_id = $5
+             RETURNING *",
+        )
+        .bind(task_id)
+        .bind(minutes)
+        .bind(spent_on)
+        .bind(note)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(entry)
+    }
+
+    async fn delete_time_entry(&self, user_id: Uuid, task_id: Uuid, id: Uuid) -> RepoResult<bool> {
+        let deleted = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM task_time_entries e
+             USING tasks t
+             WHERE e.id = $1 AND e.task_id = $2 AND t.id = e.task_id AND t.user_id = $3
+             RETURNING e.id",
+        )
+        .bind(id)
+        .bind(task_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(deleted.is_some())
+    }
+}
@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::user::User,
+    repo::{
+        RepoResult,
+        users::{NewUser, UserRepo},
+    },
+};
+
+pub struct PgUserRepo {
+    pool: PgPool,
+}
+
+impl PgUserRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepo for PgUserRepo {
+    async fn create(&self, user: NewUser) -> RepoResult<User> {
+        let user = sqlx::query_as::<_, User>(
+            "INSERT INTO users (username, email, password_hash, timezone) \
+             VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(user.username)
+        .bind(user.email)
+        .bind(user.password_hash)
+        .bind(user.timezone)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(user)
+    }
+
+    async fn find(&self, id: Uuid) -> RepoResult<Option<User>> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(user)
+    }
+
+    async fn find_by_email(&self, email: &str) -> RepoResult<Option<User>> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(user)
+    }
+
+    async fn update_timezone(
+        &self,
+        id: Uuid,
+        timezone: Option<String>,
+    ) -> RepoResult<Option<User>> {
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users
+             SET timezone = COALESCE($2, timezone)
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(timezone)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(user)
+    }
+
+    async fn timezone(&self, id: Uuid) -> RepoResult<Option<String>> {
+        let name = sqlx::query_scalar::<_, String>("SELECT timezone FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(name)
+    }
+}
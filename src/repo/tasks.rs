@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{
+    models::task::{CreateTask, Task, TimeEntry, UpdateTask},
+    repo::RepoResult,
+};
+
+// A task row plus whether the statement that returned it is what completed it
+#[derive(Debug, sqlx::FromRow)]
+pub struct UpdatedTask {
+    #[sqlx(flatten)]
+    pub task: Task,
+    pub newly_completed: bool,
+}
+
+// What tasks::effort needs to work out a task's effort. A pomodoro is as long
+// as it was when the estimate was made: the user's focus_minutes then, or the
+// default 25 minutes.
+#[derive(Debug, sqlx::FromRow)]
+pub struct EffortRow {
+    #[sqlx(flatten)]
+    pub task: Task,
+    pub minutes_per_pomodoro: i32,
+    pub focus_seconds: i64,
+    pub completed_pomodoros: i64,
+    pub manual_minutes: i64,
+}
+
+// Tags are expected normalized and goal links checked by the caller
+#[async_trait]
+pub trait TaskRepo: Send + Sync {
+    async fn list(&self, user_id: Uuid) -> RepoResult<Vec<Task>>;
+
+    async fn find(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<Task>>;
+
+    // All of the user's tasks, oldest first, or just `task_id` when given
+    async fn effort(&self, user_id: Uuid, task_id: Option<Uuid>) -> RepoResult<Vec<EffortRow>>;
+
+    // Open tasks with a due date, soonest first
+    async fn open_with_due_date(&self, user_id: Uuid) -> RepoResult<Vec<Task>>;
+
+    // Tasks whose completed_at falls on a date after `after` in the user's timezone `tz`
+    async fn count_completed_after(
+        &self,
+        user_id: Uuid,
+        tz: Tz,
+        after: NaiveDate,
+    ) -> RepoResult<i64>;
+
+    async fn create(&self, user_id: Uuid, task: CreateTask) -> RepoResult<Task>;
+
+    // Setting `completed` stamps completed_at on the transition only
+    async fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        changes: UpdateTask,
+    ) -> RepoResult<Option<UpdatedTask>>;
+
+    // Completing a completed task keeps its original completed_at
+    async fn complete(&self, user_id: Uuid, id: Uuid) -> RepoResult<Option<UpdatedTask>>;
+
+    // Time entries go with the task; linked pomodoro sessions are kept, unlinked
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> RepoResult<bool>;
+
+    // Oldest first; empty when the task isn't the user's
+    async fn time_entries(&self, user_id: Uuid, task_id: Uuid) -> RepoResult<Vec<TimeEntry>>;
+
+    // None when the task isn't the user's
+    async fn add_time_entry(
+        &self,
+        user_id: Uuid,
+        task_id: Uuid,
+        minutes: i32,
+        spent_on: NaiveDate,
+        note: Option<String>,
+    ) -> RepoResult<Option<TimeEntry>>;
+
+    async fn delete_time_entry(&self, user_id: Uuid, task_id: Uuid, id: Uuid) -> RepoResult<bool>;
+}
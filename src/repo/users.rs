@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{models::user::User, repo::RepoResult};
+
+pub struct NewUser {
+    pub username: String,
+    pub email: String,
+    pub password_hash: String,
+    pub timezone: String,
+}
+
+#[async_trait]
+pub trait UserRepo: Send + Sync {
+    // Err(Duplicate) when the username or email is taken
+    async fn create(&self, user: NewUser) -> RepoResult<User>;
+
+    async fn find(&self, id: Uuid) -> RepoResult<Option<User>>;
+
+    async fn find_by_email(&self, email: &str) -> RepoResult<Option<User>>;
+
+    // None keeps the current timezone
+    async fn update_timezone(&self, id: Uuid, timezone: Option<String>)
+    -> RepoResult<Option<User>>;
+
+    // The stored IANA name, unvalidated; see timezone::user_timezone
+    async fn timezone(&self, id: Uuid) -> RepoResult<Option<String>>;
+}
@@ -0,0 +1,69 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::watch;
+
+// Process-wide shutdown flag. Once triggered, /readyz fails, background jobs
+// stop after their current round and live streams close so connections can
+// drain.
+#[derive(Clone)]
+pub struct Shutdown {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    // Resolves once `trigger` has been called, immediately if it already was
+    pub async fn triggered(&self) {
+        let mut receiver = self.sender.subscribe();
+        // Only errors if the sender is dropped, and we hold it
+        let _ = receiver.wait_for(|&triggered| triggered).await;
+    }
+}
+
+// Waits for Ctrl-C or SIGTERM, flips `shutdown`, then keeps serving for
+// `delay` so load balancers see /readyz fail before the listener closes
+pub async fn on_signal(shutdown: Shutdown, delay: Duration) {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    tracing::info!("shutting down");
+    shutdown.trigger();
+    tokio::time::sleep(delay).await;
+}
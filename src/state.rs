@@ -1,16 +1,41 @@
+use chrono::Duration;
 use sqlx::PgPool;
 
+use crate::{pomodoro::events::SessionEvents, repo::Repos, shutdown::Shutdown};
+
 #[derive(Clone)]
 pub struct AppState {
+    // Handlers go through `repos`; the pool is for health checks, metrics,
+    // session events and the reaper
     pub db_pool: PgPool,
+    pub repos: Repos,
     pub jwt_secret: String,
+    pub token_lifetime: Duration,
+    pub session_events: SessionEvents,
+    pub shutdown: Shutdown,
 }
 
 impl AppState {
-    pub fn new(db_pool: PgPool, jwt_secret: String) -> Self {
+    pub fn new(
+        db_pool: PgPool,
+        jwt_secret: String,
+        token_lifetime: Duration,
+        session_events: SessionEvents,
+        shutdown: Shutdown,
+    ) -> Self {
         Self {
+            repos: Repos::postgres(db_pool.clone()),
             db_pool,
             jwt_secret,
+            token_lifetime,
+            session_events,
+            shutdown,
         }
     }
+
+    // Swaps the Postgres repositories, e.g. for Repos::in_memory() in tests
+    pub fn with_repos(mut self, repos: Repos) -> Self {
+        self.repos = repos;
+        self
+    }
 }
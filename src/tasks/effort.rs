@@ -0,0 +1,45 @@
+use uuid::Uuid;
+
+use crate::{
+    models::task::{TaskEffort, TaskWithEffort},
+    repo::{RepoResult, TaskRepo, tasks::EffortRow},
+};
+
+// All of the user's tasks with their effort, or just `task_id` when given
+pub async fn tasks_with_effort(
+    tasks: &dyn TaskRepo,
+    user_id: Uuid,
+    task_id: Option<Uuid>,
+) -> RepoResult<Vec<TaskWithEffort>> {
+    let rows = tasks.effort(user_id, task_id).await?;
+    Ok(rows.into_iter().map(with_effort).collect())
+}
+
+fn with_effort(row: EffortRow) -> TaskWithEffort {
+    let estimated_minutes = row.task.estimated_minutes.map(i64::from).or_else(|| {
+        row.task
+            .estimated_pomodoros
+            .map(|pomodoros| pomodoros as i64 * row.minutes_per_pomodoro as i64)
+    });
+    let focus_minutes = row.focus_seconds as f64 / 60.0;
+    let actual_minutes = focus_minutes + row.manual_minutes as f64;
+
+    TaskWithEffort {
+        effort: TaskEffort {
+            estimated_minutes,
+            focus_minutes: round(focus_minutes),
+            manual_minutes: row.manual_minutes,
+            actual_minutes: round(actual_minutes),
+            completed_pomodoros: row.completed_pomodoros,
+            estimate_ratio: estimated_minutes
+                .filter(|&estimate| estimate > 0)
+                .map(|estimate| round(actual_minutes / estimate as f64)),
+        },
+        task: row.task,
+    }
+}
+
+// Two decimal places is plenty for minutes and ratios
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
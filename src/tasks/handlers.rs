@@ -4,160 +4,258 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use serde_json::json;
 use uuid::Uuid;
 
 use crate::{
     auth::middleware::AuthUser,
-    models::task::{CreateTask, Task, UpdateTask},
+    error::{AppError, Problem},
+    metrics,
+    models::task::{
+        CreateTask, CreateTimeEntry, TaskDetailResponse, TaskResponse, TasksResponse,
+        TimeEntriesResponse, TimeEntryResponse, UpdateTask,
+    },
     state::AppState,
+    tasks::effort::tasks_with_effort,
+    timezone::user_today,
+    validation::ValidatedJson,
 };
 
-pub async fn get_tasks(auth: AuthUser, State(state): State<AppState>) -> impl IntoResponse {
-    let tasks = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE user_id = $1")
-        .bind(auth.user_id)
-        .fetch_all(&state.db_pool)
-        .await;
-
-    match tasks {
-        Ok(tasks) => (StatusCode::OK, Json(json!({"tasks": tasks}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch tasks"})),
-        )
-            .into_response(),
+#[utoipa::path(get, path = "/", tag = "tasks", responses((status = OK, body = TasksResponse)))]
+pub async fn get_tasks(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let tasks = state.repos.tasks.list(auth.user_id).await?;
+
+    Ok((StatusCode::OK, Json(TasksResponse { tasks })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "tasks",
+    responses((status = OK, description = "The task with its effort", body = TaskDetailResponse))
+)]
+pub async fn get_task(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let task = tasks_with_effort(&*state.repos.tasks, auth.user_id, Some(id))
+        .await?
+        .pop()
+        .ok_or(AppError::NotFound("Task"))?;
+
+    Ok((StatusCode::OK, Json(TaskDetailResponse { task })))
+}
+
+// A task may only be linked to a goal owned by the same user
+async fn check_goal(
+    state: &AppState,
+    user_id: Uuid,
+    goal_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    if let Some(goal_id) = goal_id
+        && state.repos.goals.find(user_id, goal_id).await?.is_none()
+    {
+        return Err(AppError::NotFound("Goal"));
     }
+    Ok(())
 }
 
+// Tags are trimmed, lowercased and deduplicated so "Work" and "work " group together
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "tasks",
+    request_body = CreateTask,
+    responses(
+        (status = CREATED, body = TaskResponse),
+        (status = NOT_FOUND, description = "goal_id names no goal of yours", body = Problem,
+         content_type = "application/problem+json"),
+    )
+)]
 pub async fn create_task(
     auth: AuthUser,
     State(state): State<AppState>,
-    Json(body): Json<CreateTask>,
-) -> impl IntoResponse {
-    let task = sqlx::query_as::<_, Task>(
-        "INSERT INTO tasks (user_id, title, notes, priority, due_date) \
-         VALUES ($1, $2, $3, COALESCE($4, 'medium'), $5) \
-         RETURNING *",
-    )
-    .bind(auth.user_id)
-    .bind(body.title)
-    .bind(body.notes)
-    .bind(body.priority)
-    .bind(body.due_date)
-    .fetch_one(&state.db_pool)
-    .await;
-
-    match task {
-        Ok(task) => (StatusCode::CREATED, Json(json!({"task": task}))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to create task"})),
+    ValidatedJson(body): ValidatedJson<CreateTask>,
+) -> Result<impl IntoResponse, AppError> {
+    check_goal(&state, auth.user_id, body.goal_id).await?;
+
+    let tags = normalize_tags(body.tags.unwrap_or_default());
+    let task = state
+        .repos
+        .tasks
+        .create(
+            auth.user_id,
+            CreateTask {
+                tags: Some(tags),
+                ..body
+            },
         )
-            .into_response(),
-    }
+        .await?;
+    metrics::task_created();
+
+    Ok((StatusCode::CREATED, Json(TaskResponse { task })))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "tasks",
+    request_body = UpdateTask,
+    responses((status = OK, body = TaskResponse))
+)]
 pub async fn update_task(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    Json(body): Json<UpdateTask>,
-) -> impl IntoResponse {
-    let task = sqlx::query_as::<_, Task>(
-        "UPDATE tasks
-         SET title = COALESCE($3, title),
-             notes = COALESCE($4, notes),
-             priority = COALESCE($5, priority),
-             due_date = COALESCE($6, due_date),
-             completed = COALESCE($7, completed),
-             completed_at = CASE
-                 WHEN COALESCE($7, completed) = TRUE AND completed = FALSE THEN NOW()
-                 WHEN COALESCE($7, completed) = FALSE THEN NULL
-                 ELSE completed_at
-             END
-         WHERE id = $1 AND user_id = $2
-         RETURNING *",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .bind(body.title)
-    .bind(body.notes)
-    .bind(body.priority)
-    .bind(body.due_date)
-    .bind(body.completed)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match task {
-        Ok(Some(task)) => (StatusCode::OK, Json(json!({"task": task}))).into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Task not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to update task"})),
-        )
-            .into_response(),
+    ValidatedJson(body): ValidatedJson<UpdateTask>,
+) -> Result<impl IntoResponse, AppError> {
+    check_goal(&state, auth.user_id, body.goal_id.flatten()).await?;
+
+    let tags = body.tags.map(normalize_tags);
+    let updated = state
+        .repos
+        .tasks
+        .update(auth.user_id, id, UpdateTask { tags, ..body })
+        .await?
+        .ok_or(AppError::NotFound("Task"))?;
+    if updated.newly_completed {
+        metrics::task_completed();
     }
+
+    Ok((StatusCode::OK, Json(TaskResponse { task: updated.task })))
 }
 
+#[utoipa::path(delete, path = "/{id}", tag = "tasks", responses((status = NO_CONTENT)))]
 pub async fn delete_task(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    let deleted = sqlx::query_scalar::<_, Uuid>(
-        "DELETE FROM tasks WHERE id = $1 AND user_id = $2 RETURNING id",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match deleted {
-        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Task not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to delete task"})),
-        )
-            .into_response(),
+) -> Result<impl IntoResponse, AppError> {
+    if !state.repos.tasks.delete(auth.user_id, id).await? {
+        return Err(AppError::NotFound("Task"));
     }
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(post, path = "/{id}/complete", tag = "tasks", responses((status = OK, body = TaskResponse)))]
 pub async fn complete_task(
     auth: AuthUser,
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    let task = sqlx::query_as::<_, Task>(
-        "UPDATE tasks
-         SET completed = TRUE, completed_at = NOW()
-         WHERE id = $1 AND user_id = $2
-         RETURNING *",
-    )
-    .bind(id)
-    .bind(auth.user_id)
-    .fetch_optional(&state.db_pool)
-    .await;
-
-    match task {
-        Ok(Some(task)) => (StatusCode::OK, Json(json!({"task": task}))).into_response(),
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Task not found"})),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to complete task"})),
-        )
-            .into_response(),
+) -> Result<impl IntoResponse, AppError> {
+    // Completing a completed task keeps its original completed_at, as PATCH does
+    let updated = state
+        .repos
+        .tasks
+        .complete(auth.user_id, id)
+        .await?
+        .ok_or(AppError::NotFound("Task"))?;
+    if updated.newly_completed {
+        metrics::task_completed();
+    }
+
+    Ok((StatusCode::OK, Json(TaskResponse { task: updated.task })))
+}
+
+async fn owns_task(state: &AppState, user_id: Uuid, task_id: Uuid) -> Result<bool, AppError> {
+    Ok(state.repos.tasks.find(user_id, task_id).await?.is_some())
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/time-entries",
+    tag = "tasks",
+    responses((status = OK, body = TimeEntriesResponse))
+)]
+pub async fn get_time_entries(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    if !owns_task(&state, auth.user_id, id).await? {
+        return Err(AppError::NotFound("Task"));
+    }
+
+    let entries = state.repos.tasks.time_entries(auth.user_id, id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TimeEntriesResponse {
+            time_entries: entries,
+        }),
+    ))
+}
+
+// spent_on defaults to today in the user's timezone
+#[utoipa::path(
+    post,
+    path = "/{id}/time-entries",
+    tag = "tasks",
+    request_body = CreateTimeEntry,
+    responses((status = CREATED, body = TimeEntryResponse))
+)]
+pub async fn create_time_entry(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<CreateTimeEntry>,
+) -> Result<impl IntoResponse, AppError> {
+    if !owns_task(&state, auth.user_id, id).await? {
+        return Err(AppError::NotFound("Task"));
+    }
+
+    let spent_on = match body.spent_on {
+        Some(date) => date,
+        None => user_today(&*state.repos.users, auth.user_id).await?,
+    };
+
+    let entry = state
+        .repos
+        .tasks
+        .add_time_entry(auth.user_id, id, body.minutes, spent_on, body.note)
+        .await?
+        .ok_or(AppError::NotFound("Task"))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(TimeEntryResponse { time_entry: entry }),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}/time-entries/{entry_id}",
+    tag = "tasks",
+    responses((status = NO_CONTENT))
+)]
+pub async fn delete_time_entry(
+    auth: AuthUser,
+    Path((id, entry_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    if !state
+        .repos
+        .tasks
+        .delete_time_entry(auth.user_id, id, entry_id)
+        .await?
+    {
+        return Err(AppError::NotFound("Time entry"));
     }
+
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -1,2 +1,3 @@
+pub mod effort;
 pub mod handlers;
 pub mod router;
@@ -1,14 +1,20 @@
-use axum::{
-    Router,
-    routing::{get, patch, post},
-};
+use utoipa_axum::routes;
 
-use crate::state::AppState;
-use crate::tasks::handlers::{complete_task, create_task, delete_task, get_tasks, update_task};
+use crate::openapi::ApiRouter;
+use crate::tasks::handlers;
 
-pub fn tasks_router() -> Router<AppState> {
-    Router::new()
-        .route("/", get(get_tasks).post(create_task))
-        .route("/{id}", patch(update_task).delete(delete_task))
-        .route("/{id}/complete", post(complete_task))
+pub fn tasks_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(handlers::get_tasks, handlers::create_task))
+        .routes(routes!(
+            handlers::get_task,
+            handlers::update_task,
+            handlers::delete_task
+        ))
+        .routes(routes!(handlers::complete_task))
+        .routes(routes!(
+            handlers::get_time_entries,
+            handlers::create_time_entry
+        ))
+        .routes(routes!(handlers::delete_time_entry))
 }
@@ -0,0 +1,21 @@
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::repo::{RepoResult, UserRepo};
+
+// Users with a missing or unrecognised timezone are treated as UTC
+pub async fn user_timezone(users: &dyn UserRepo, user_id: Uuid) -> RepoResult<Tz> {
+    let name = users.timezone(user_id).await?;
+    Ok(name.and_then(|n| n.parse().ok()).unwrap_or(Tz::UTC))
+}
+
+// The calendar date it currently is for the user, e.g. for default check-in dates
+pub async fn user_today(users: &dyn UserRepo, user_id: Uuid) -> RepoResult<NaiveDate> {
+    let tz = user_timezone(users, user_id).await?;
+    Ok(today_in(tz))
+}
+
+pub fn today_in(tz: Tz) -> NaiveDate {
+    Utc::now().with_timezone(&tz).date_naive()
+}
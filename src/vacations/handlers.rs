@@ -0,0 +1,71 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::middleware::AuthUser,
+    error::AppError,
+    models::vacation::{CreateVacation, VacationResponse, VacationsResponse},
+    state::AppState,
+    timezone::user_today,
+    validation::ValidatedJson,
+};
+
+#[utoipa::path(get, path = "/", tag = "vacations", responses((status = OK, body = VacationsResponse)))]
+pub async fn get_vacations(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let vacations = state.repos.habits.vacations(auth.user_id).await?;
+
+    Ok((StatusCode::OK, Json(VacationsResponse { vacations })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "vacations",
+    request_body = CreateVacation,
+    responses((status = CREATED, body = VacationResponse))
+)]
+pub async fn create_vacation(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    ValidatedJson(body): ValidatedJson<CreateVacation>,
+) -> Result<impl IntoResponse, AppError> {
+    // Like skips, a vacation can't excuse days already missed; that costs a freeze
+    if body.starts_on < user_today(&*state.repos.users, auth.user_id).await? {
+        return Err(AppError::field(
+            "starts_on",
+            "must not be in the past; spend a streak freeze on a missed day",
+        ));
+    }
+    if body.ends_on < body.starts_on {
+        return Err(AppError::field("ends_on", "must not be before starts_on"));
+    }
+
+    let vacation = state
+        .repos
+        .habits
+        .create_vacation(auth.user_id, body)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(VacationResponse { vacation })))
+}
+
+#[utoipa::path(delete, path = "/{id}", tag = "vacations", responses((status = NO_CONTENT)))]
+pub async fn delete_vacation(
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    if !state.repos.habits.delete_vacation(auth.user_id, id).await? {
+        return Err(AppError::NotFound("Vacation"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,10 @@
+use utoipa_axum::routes;
+
+use crate::openapi::ApiRouter;
+use crate::vacations::handlers;
+
+pub fn vacations_router() -> ApiRouter {
+    ApiRouter::new()
+        .routes(routes!(handlers::get_vacations, handlers::create_vacation))
+        .routes(routes!(handlers::delete_vacation))
+}
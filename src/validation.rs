@@ -0,0 +1,135 @@
+use std::borrow::Cow;
+
+use axum::{
+    Json,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, de::DeserializeOwned};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::error::{AppError, FieldError};
+
+pub const MAX_TITLE_LEN: u64 = 255;
+pub const MAX_TEXT_LEN: u64 = 10_000;
+pub const MAX_NOTE_LEN: u64 = 1_000;
+pub const MAX_TAGS: usize = 20;
+pub const MAX_TAG_LEN: usize = 50;
+
+// Drop-in replacement for `Json` that also runs the payload's `Validate` rules.
+// Malformed JSON is a 400; a body of the wrong shape or with invalid values is
+// a 422 listing every offending field.
+pub struct ValidatedJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| match rejection {
+                JsonRejection::JsonDataError(err) => AppError::field("body", err.body_text()),
+                other => AppError::BadRequest(other.body_text()),
+            })?;
+        value.validate()?;
+        Ok(ValidatedJson(value))
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(field_errors(&errors))
+    }
+}
+
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut fields: Vec<FieldError> = errors
+        .field_errors()
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            errors.iter().map(move |error| FieldError {
+                field: field.to_string(),
+                message: describe(error),
+            })
+        })
+        .collect();
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    fields
+}
+
+// Built-in rules carry no message, so spell them out from their parameters
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    let param = |name: &str| error.params.get(name).map(|value| value.to_string());
+
+    match error.code.as_ref() {
+        "length" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {min} and {max} characters long"),
+            (Some(min), None) => format!("must be at least {min} characters long"),
+            (None, Some(max)) => format!("must be at most {max} characters long"),
+            (None, None) => "has an invalid length".to_string(),
+        },
+        "range" => match (param("min"), param("max"), param("exclusive_min")) {
+            (Some(min), Some(max), _) => format!("must be between {min} and {max}"),
+            (Some(min), None, _) => format!("must be at least {min}"),
+            (None, Some(max), _) => format!("must be at most {max}"),
+            (None, None, Some(_)) => "must be positive".to_string(),
+            (None, None, None) => "is out of range".to_string(),
+        },
+        "email" => "must be a valid email address".to_string(),
+        code => format!("is invalid ({code})"),
+    }
+}
+
+fn invalid(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(invalid("blank", "must not be blank"));
+    }
+    Ok(())
+}
+
+// The request carries no timezone, so "today" is taken as the earliest local
+// date anywhere (UTC-12); the handlers still work in the user's own zone
+pub fn not_in_past(date: &NaiveDate) -> Result<(), ValidationError> {
+    let earliest_today = (Utc::now() - Duration::hours(12)).date_naive();
+    if *date < earliest_today {
+        return Err(invalid("past", "must not be in the past"));
+    }
+    Ok(())
+}
+
+pub fn valid_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > MAX_TAGS {
+        return Err(invalid("tags", "must have at most 20 tags"));
+    }
+    if tags
+        .iter()
+        .any(|tag| tag.trim().chars().count() > MAX_TAG_LEN)
+    {
+        return Err(invalid(
+            "tags",
+            "each tag must be at most 50 characters long",
+        ));
+    }
+    Ok(())
+}
+
+// For `#[serde(default, deserialize_with = "nullable")]` on an Option<Option<T>>
+// field: a missing field stays None, an explicit null becomes Some(None)
+pub fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
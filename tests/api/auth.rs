@@ -0,0 +1,146 @@
+use axum::http::{Method, StatusCode};
+use chrono::Duration;
+use projekt::{auth::jwt::create_token, db::MIGRATOR};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::common::{PASSWORD, TEST_JWT_SECRET, TestApp};
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn register_login_and_me(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let me = ann.get("/auth/me").await.expect(StatusCode::OK);
+    assert_eq!(me["user"]["username"], "ann");
+    assert_eq!(me["user"]["email"], "ann@example.com");
+    assert_eq!(me["user"]["id"], ann.user_id.to_string());
+    assert_eq!(me["user"]["timezone"], "UTC");
+    assert!(me["user"].get("password_hash").is_none());
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn register_rejects_taken_names_and_bad_input(pool: PgPool) {
+    let app = TestApp::new(pool);
+    app.user("ann").await;
+    let anonymous = app.anonymous();
+
+    anonymous
+        .post(
+            "/auth/register",
+            json!({"username": "other", "email": "ann@example.com", "password": PASSWORD}),
+        )
+        .await
+        .expect_problem(StatusCode::CONFLICT, "email_taken");
+    anonymous
+        .post(
+            "/auth/register",
+            json!({"username": "ann", "email": "other@example.com", "password": PASSWORD}),
+        )
+        .await
+        .expect_problem(StatusCode::CONFLICT, "username_taken");
+
+    let invalid = anonymous
+        .post(
+            "/auth/register",
+            json!({"username": "bob", "email": "not-an-email", "password": "short"}),
+        )
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    let fields: Vec<_> = invalid["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["field"].as_str().unwrap())
+        .collect();
+    assert!(fields.contains(&"email") && fields.contains(&"password"));
+
+    anonymous
+        .post(
+            "/auth/register",
+            json!({
+                "username": "bob",
+                "email": "bob@example.com",
+                "password": PASSWORD,
+                "timezone": "Mars/Olympus_Mons",
+            }),
+        )
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    anonymous
+        .send(
+            Method::POST,
+            "/auth/register",
+            Some("{not json".to_string()),
+        )
+        .await
+        .expect_problem(StatusCode::BAD_REQUEST, "bad_request");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn login_rejects_wrong_credentials(pool: PgPool) {
+    let app = TestApp::new(pool);
+    app.user("ann").await;
+    let anonymous = app.anonymous();
+
+    anonymous
+        .post(
+            "/auth/login",
+            json!({"email": "ann@example.com", "password": "wrong password"}),
+        )
+        .await
+        .expect_problem(StatusCode::UNAUTHORIZED, "unauthorized");
+    anonymous
+        .post(
+            "/auth/login",
+            json!({"email": "nobody@example.com", "password": PASSWORD}),
+        )
+        .await
+        .expect_problem(StatusCode::UNAUTHORIZED, "unauthorized");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn protected_routes_need_a_valid_token(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    app.anonymous()
+        .get("/auth/me")
+        .await
+        .expect_problem(StatusCode::UNAUTHORIZED, "unauthorized");
+    ann.with_token("not.a.jwt")
+        .get("/tasks")
+        .await
+        .expect_problem(StatusCode::UNAUTHORIZED, "unauthorized");
+
+    let forged = create_token(ann.user_id, "some other secret", Duration::hours(1)).unwrap();
+    ann.with_token(&forged)
+        .get("/tasks")
+        .await
+        .expect_problem(StatusCode::UNAUTHORIZED, "unauthorized");
+
+    let expired = create_token(ann.user_id, TEST_JWT_SECRET, Duration::hours(-1)).unwrap();
+    ann.with_token(&expired)
+        .get("/tasks")
+        .await
+        .expect_problem(StatusCode::UNAUTHORIZED, "unauthorized");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn update_me_changes_timezone(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let updated = ann
+        .patch("/auth/me", json!({"timezone": "Europe/Tallinn"}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(updated["user"]["timezone"], "Europe/Tallinn");
+
+    ann.patch("/auth/me", json!({"timezone": "Nowhere/Special"}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    let me = ann.get("/auth/me").await.expect(StatusCode::OK);
+    assert_eq!(me["user"]["timezone"], "Europe/Tallinn");
+}
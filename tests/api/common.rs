@@ -0,0 +1,229 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderMap, Method, Request, StatusCode, header},
+    response::Response,
+};
+use projekt::{
+    config::Config, pomodoro::events::SessionEvents, repo::Repos, shutdown::Shutdown,
+    state::AppState,
+};
+use serde_json::{Value, json};
+use sqlx::{PgPool, postgres::PgPoolOptions};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub const PASSWORD: &str = "correct horse battery";
+pub const TEST_JWT_SECRET: &str = "integration-test-secret";
+
+// The full application on a database of its own. Tests get the pool from
+// #[sqlx::test], which creates and migrates a fresh database for each one.
+#[derive(Clone)]
+pub struct TestApp {
+    router: Router,
+}
+
+impl TestApp {
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_config(pool, &Config::default())
+    }
+
+    pub fn with_config(pool: PgPool, config: &Config) -> Self {
+        Self {
+            router: projekt::app(config, Self::state(pool)),
+        }
+    }
+
+    // The application on the in-memory repositories. The pool never connects,
+    // so only handlers that stay on the repos work.
+    pub fn in_memory() -> Self {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(200))
+            .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+            .unwrap();
+        let state = Self::state(pool).with_repos(Repos::in_memory());
+        Self {
+            router: projekt::app(&Config::default(), state),
+        }
+    }
+
+    fn state(pool: PgPool) -> AppState {
+        AppState::new(
+            pool,
+            TEST_JWT_SECRET.to_string(),
+            chrono::Duration::hours(1),
+            SessionEvents::new(None),
+            Shutdown::new(),
+        )
+    }
+
+    pub fn anonymous(&self) -> Client {
+        Client {
+            app: self.clone(),
+            token: None,
+            user_id: Uuid::nil(),
+        }
+    }
+
+    // Registers `name` (as name@example.com) and logs in
+    pub async fn user(&self, name: &str) -> Client {
+        let anonymous = self.anonymous();
+        let email = format!("{name}@example.com");
+        let user = anonymous
+            .post(
+                "/auth/register",
+                json!({"username": name, "email": email, "password": PASSWORD}),
+            )
+            .await
+            .expect(StatusCode::CREATED);
+        let login = anonymous
+            .post("/auth/login", json!({"email": email, "password": PASSWORD}))
+            .await
+            .expect(StatusCode::OK);
+
+        Client {
+            app: self.clone(),
+            token: Some(login["token"].as_str().unwrap().to_string()),
+            user_id: user["user"]["id"].as_str().unwrap().parse().unwrap(),
+        }
+    }
+
+    async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.open(request).await;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        TestResponse {
+            status,
+            headers,
+            text,
+            body,
+        }
+    }
+
+    async fn open(&self, request: Request<Body>) -> Response {
+        self.router.clone().oneshot(request).await.unwrap()
+    }
+}
+
+// Sends requests as one user, or as nobody
+pub struct Client {
+    app: TestApp,
+    token: Option<String>,
+    pub user_id: Uuid,
+}
+
+impl Client {
+    pub async fn request(&self, method: Method, uri: &str, body: Option<Value>) -> TestResponse {
+        match body {
+            Some(body) => self.send(method, uri, Some(body.to_string())).await,
+            None => self.send(method, uri, None).await,
+        }
+    }
+
+    // `body` is sent as JSON whether or not it parses
+    pub async fn send(&self, method: Method, uri: &str, body: Option<String>) -> TestResponse {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = &self.token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let body = match body {
+            Some(body) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(body)
+            }
+            None => Body::empty(),
+        };
+        self.app.send(request.body(body).unwrap()).await
+    }
+
+    // For endpoints that never finish their body, like the SSE stream
+    pub async fn open(&self, uri: &str) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(token) = &self.token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        self.app.open(request.body(Body::empty()).unwrap()).await
+    }
+
+    // The same user with a different (e.g. forged) token
+    pub fn with_token(&self, token: &str) -> Client {
+        Client {
+            app: self.app.clone(),
+            token: Some(token.to_string()),
+            user_id: self.user_id,
+        }
+    }
+
+    pub async fn get(&self, uri: &str) -> TestResponse {
+        self.request(Method::GET, uri, None).await
+    }
+
+    pub async fn post(&self, uri: &str, body: Value) -> TestResponse {
+        self.request(Method::POST, uri, Some(body)).await
+    }
+
+    pub async fn put(&self, uri: &str, body: Value) -> TestResponse {
+        self.request(Method::PUT, uri, Some(body)).await
+    }
+
+    pub async fn patch(&self, uri: &str, body: Value) -> TestResponse {
+        self.request(Method::PATCH, uri, Some(body)).await
+    }
+
+    pub async fn delete(&self, uri: &str) -> TestResponse {
+        self.request(Method::DELETE, uri, None).await
+    }
+
+    // Creates a resource and returns its id; `key` is the envelope field
+    pub async fn create(&self, uri: &str, key: &str, body: Value) -> String {
+        let created = self.post(uri, body).await.expect(StatusCode::CREATED);
+        created[key]["id"].as_str().unwrap().to_string()
+    }
+}
+
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub text: String,
+    pub body: Value,
+}
+
+impl TestResponse {
+    // Checks the status and hands back the JSON body
+    #[track_caller]
+    pub fn expect(self, status: StatusCode) -> Value {
+        assert_eq!(self.status, status, "unexpected response: {}", self.text);
+        self.body
+    }
+
+    // Checks that this is a problem document with the given status and code
+    #[track_caller]
+    pub fn expect_problem(self, status: StatusCode, code: &str) -> Value {
+        assert_eq!(
+            self.headers[header::CONTENT_TYPE],
+            "application/problem+json",
+            "not a problem document: {}",
+            self.text
+        );
+        let body = self.expect(status);
+        assert_eq!(body["code"], code, "unexpected problem: {body}");
+        body
+    }
+}
+
+pub fn today() -> chrono::NaiveDate {
+    chrono::Utc::now().date_naive()
+}
+
+pub fn days_ago(days: i64) -> String {
+    (today() - chrono::Duration::days(days)).to_string()
+}
+
+pub fn days_ahead(days: i64) -> String {
+    (today() + chrono::Duration::days(days)).to_string()
+}
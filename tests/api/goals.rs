@@ -0,0 +1,96 @@
+use axum::http::StatusCode;
+use projekt::db::MIGRATOR;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::common::{TestApp, days_ahead};
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn goal_crud(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let created = ann
+        .post(
+            "/goals",
+            json!({"title": "Run a marathon", "description": "Spring", "deadline": days_ahead(90)}),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(created["goal"]["status"], "active");
+    let id = created["goal"]["id"].as_str().unwrap();
+
+    let updated = ann
+        .patch(
+            &format!("/goals/{id}"),
+            json!({"title": "Run a half marathon"}),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(updated["goal"]["title"], "Run a half marathon");
+    assert_eq!(updated["goal"]["description"], "Spring");
+
+    ann.patch(&format!("/goals/{id}"), json!({"status": "someday"}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    let completed = ann
+        .post(&format!("/goals/{id}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(completed["goal"]["status"], "completed");
+
+    ann.delete(&format!("/goals/{id}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.delete(&format!("/goals/{id}"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+    let list = ann.get("/goals").await.expect(StatusCode::OK);
+    assert_eq!(list["goals"], json!([]));
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn goals_are_forecast_from_their_tasks(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let goal = ann
+        .create(
+            "/goals",
+            "goal",
+            json!({"title": "Launch", "deadline": days_ahead(30)}),
+        )
+        .await;
+    let done = ann
+        .create("/tasks", "task", json!({"title": "Plan", "goal_id": goal}))
+        .await;
+    ann.create("/tasks", "task", json!({"title": "Build", "goal_id": goal}))
+        .await;
+    ann.post(&format!("/tasks/{done}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    let abandoned = ann
+        .create("/goals", "goal", json!({"title": "Learn the harp"}))
+        .await;
+    ann.patch(
+        &format!("/goals/{abandoned}"),
+        json!({"status": "abandoned"}),
+    )
+    .await
+    .expect(StatusCode::OK);
+
+    let list = ann.get("/goals").await.expect(StatusCode::OK);
+    let goals = list["goals"].as_array().unwrap();
+    assert_eq!(goals.len(), 2);
+    let launch = goals.iter().find(|g| g["id"] == goal.as_str()).unwrap();
+    let forecast = &launch["forecast"];
+    assert_eq!(forecast["total_tasks"], 2);
+    assert_eq!(forecast["completed_tasks"], 1);
+    assert_eq!(forecast["completion_rate"], 0.5);
+    assert_eq!(forecast["risk"], "on_track");
+    let harp = goals
+        .iter()
+        .find(|g| g["id"] == abandoned.as_str())
+        .unwrap();
+    assert!(harp["forecast"].is_null());
+}
@@ -0,0 +1,465 @@
+use axum::http::StatusCode;
+use projekt::{commands, db::MIGRATOR};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::common::{Client, TestApp, days_ago, days_ahead, today};
+
+async fn habit_ids(client: &Client, uri: &str) -> Vec<String> {
+    let list = client.get(uri).await.expect(StatusCode::OK);
+    list["habits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|habit| habit["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+async fn streak_freezes(client: &Client) -> i64 {
+    let me = client.get("/auth/me").await.expect(StatusCode::OK);
+    me["user"]["streak_freezes"].as_i64().unwrap()
+}
+
+// Everyone starts with two freezes; spending them on a spare habit makes any
+// award visible
+async fn spend_starting_freezes(client: &Client) {
+    let spare = client
+        .create("/habits", "habit", json!({"name": "Spare"}))
+        .await;
+    for days in [20, 21] {
+        client
+            .post(
+                &format!("/habits/{spare}/freeze"),
+                json!({"frozen_on": days_ago(days)}),
+            )
+            .await
+            .expect(StatusCode::CREATED);
+    }
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn habit_crud_and_archiving(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let created = ann
+        .post("/habits", json!({"name": "Stretch"}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(created["habit"]["frequency"], "daily");
+    let id = created["habit"]["id"].as_str().unwrap().to_string();
+    ann.post("/habits", json!({"name": ""}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    let list = ann.get("/habits").await.expect(StatusCode::OK);
+    assert_eq!(list["habits"][0]["stats"]["current_streak"], 0);
+
+    let updated = ann
+        .patch(&format!("/habits/{id}"), json!({"name": "Stretch daily"}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(updated["habit"]["name"], "Stretch daily");
+
+    let archived = ann
+        .post(&format!("/habits/{id}/archive"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert!(archived["habit"]["archived_at"].is_string());
+    assert!(habit_ids(&ann, "/habits").await.is_empty());
+    assert_eq!(
+        habit_ids(&ann, "/habits?include_archived=true").await,
+        [id.as_str()]
+    );
+    assert!(habit_ids(&ann, "/habits/due-today").await.is_empty());
+
+    let restored = ann
+        .post(&format!("/habits/{id}/unarchive"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert!(restored["habit"]["archived_at"].is_null());
+    assert_eq!(habit_ids(&ann, "/habits").await, [id.as_str()]);
+
+    ann.delete(&format!("/habits/{id}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.get(&format!("/habits/{id}/stats"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn checking_in(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann
+        .create("/habits", "habit", json!({"name": "Read"}))
+        .await;
+    assert_eq!(habit_ids(&ann, "/habits/due-today").await, [id.as_str()]);
+
+    let checked = ann
+        .post(&format!("/habits/{id}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(checked["completion"]["completed_on"], today().to_string());
+    assert_eq!(checked["freeze_earned"], false);
+    ann.post(&format!("/habits/{id}/complete"), json!({}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "already_completed");
+    assert!(habit_ids(&ann, "/habits/due-today").await.is_empty());
+
+    ann.post(
+        &format!("/habits/{id}/complete"),
+        json!({"completed_on": days_ago(1)}),
+    )
+    .await
+    .expect(StatusCode::OK);
+    let stats = ann
+        .get(&format!("/habits/{id}/stats"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(stats["stats"]["current_streak"], 2);
+    assert_eq!(stats["stats"]["total_completions"], 2);
+
+    let completions = format!("/habits/{id}/completions");
+    let all = ann.get(&completions).await.expect(StatusCode::OK);
+    assert_eq!(all["completions"].as_array().unwrap().len(), 2);
+    let recent = ann
+        .get(&format!("{completions}?from={}", today()))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(recent["completions"].as_array().unwrap().len(), 1);
+
+    ann.delete(&format!("{completions}/{}", today()))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.delete(&format!("{completions}/{}", today()))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+    assert_eq!(habit_ids(&ann, "/habits/due-today").await, [id.as_str()]);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn measured_habits_add_up_partial_entries(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann
+        .create(
+            "/habits",
+            "habit",
+            json!({"name": "Water", "unit": "glasses", "daily_target": 8.0}),
+        )
+        .await;
+    let complete = format!("/habits/{id}/complete");
+
+    ann.post(&complete, json!({}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    ann.post(&complete, json!({"value": 5.0}))
+        .await
+        .expect(StatusCode::OK);
+    let topped_up = ann
+        .post(&complete, json!({"value": 3.0}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(topped_up["completion"]["value"], 8.0);
+
+    let stats = ann
+        .get(&format!("/habits/{id}/stats"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(stats["stats"]["total_value"], 8.0);
+
+    // Backfilling a day with a partial measurement tops it up to the target
+    let yesterday = days_ago(1);
+    ann.post(&complete, json!({"value": 2.0, "completed_on": yesterday}))
+        .await
+        .expect(StatusCode::OK);
+    let backfill = ann
+        .post(
+            &format!("/habits/{id}/completions"),
+            json!({"dates": [days_ago(2), yesterday, today()]}),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(backfill["inserted"], json!([days_ago(2)]));
+    assert_eq!(backfill["topped_up"], json!([yesterday]));
+    assert_eq!(backfill["already_present"], json!([today()]));
+
+    let stats = ann
+        .get(&format!("/habits/{id}/stats"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(stats["stats"]["total_completions"], 3);
+    assert_eq!(stats["stats"]["total_value"], 24.0);
+    assert_eq!(stats["stats"]["current_streak"], 3);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn backfilled_streaks_do_not_earn_freezes(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann
+        .create("/habits", "habit", json!({"name": "Walk"}))
+        .await;
+    let completions = format!("/habits/{id}/completions");
+    spend_starting_freezes(&ann).await;
+
+    let dates: Vec<_> = (1..=6).map(days_ago).collect();
+    let backfill = ann
+        .post(&completions, json!({"dates": dates[..4]}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(backfill["inserted"].as_array().unwrap().len(), 4);
+    let backfill = ann
+        .post(&completions, json!({"dates": dates}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(backfill["inserted"].as_array().unwrap().len(), 2);
+    assert_eq!(backfill["already_present"].as_array().unwrap().len(), 4);
+    ann.post(&completions, json!({"dates": []}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    // The backfilled days keep the streak but don't earn toward a freeze
+    let checked = ann
+        .post(&format!("/habits/{id}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(checked["freeze_earned"], false);
+    let stats = ann
+        .get(&format!("/habits/{id}/stats"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(stats["stats"]["current_streak"], 7);
+    assert_eq!(stats["stats"]["longest_streak"], 7);
+    assert_eq!(streak_freezes(&ann).await, 0);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn a_streak_period_earns_one_freeze(pool: PgPool) {
+    let app = TestApp::new(pool.clone());
+    let ann = app.user("ann").await;
+    let id = ann
+        .create("/habits", "habit", json!({"name": "Read"}))
+        .await;
+    spend_starting_freezes(&ann).await;
+
+    // Six days checked in on the day, which the API can only do in real time
+    for days in 1..=6 {
+        sqlx::query(
+            "INSERT INTO habit_completions (habit_id, completed_on, same_day)
+             VALUES ($1::uuid, $2::date, TRUE)",
+        )
+        .bind(&id)
+        .bind(days_ago(days))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let complete = format!("/habits/{id}/complete");
+    let checked = ann.post(&complete, json!({})).await.expect(StatusCode::OK);
+    assert_eq!(checked["freeze_earned"], true);
+    assert_eq!(streak_freezes(&ann).await, 1);
+
+    // Undoing and redoing the check-in doesn't pay out the day again
+    for _ in 0..3 {
+        ann.delete(&format!("/habits/{id}/completions/{}", today()))
+            .await
+            .expect(StatusCode::NO_CONTENT);
+        let checked = ann.post(&complete, json!({})).await.expect(StatusCode::OK);
+        assert_eq!(checked["freeze_earned"], false);
+    }
+    assert_eq!(streak_freezes(&ann).await, 1);
+
+    // Nor does redoing a past day: backdated check-ins break the earning streak
+    ann.delete(&format!("/habits/{id}/completions/{}", days_ago(1)))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.post(&complete, json!({"completed_on": days_ago(1)}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(streak_freezes(&ann).await, 1);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn skips_and_streak_freezes(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann
+        .create("/habits", "habit", json!({"name": "Meditate"}))
+        .await;
+    let skips = format!("/habits/{id}/skips");
+
+    let skip = ann
+        .post(
+            &skips,
+            json!({"skipped_on": days_ahead(1), "note": "travel"}),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(skip["skip"]["kind"], "skip");
+    ann.post(&skips, json!({"skipped_on": days_ahead(1)}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "already_skipped");
+    ann.post(&skips, json!({}))
+        .await
+        .expect(StatusCode::CREATED);
+    // A missed day can only be excused with a freeze
+    ann.post(&skips, json!({"skipped_on": days_ago(1)}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    // Everyone starts with two freezes
+    let freeze = format!("/habits/{id}/freeze");
+    let frozen = ann
+        .post(&freeze, json!({"frozen_on": days_ago(2)}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(frozen["skip"]["kind"], "freeze");
+    assert_eq!(frozen["streak_freezes"], 1);
+    ann.post(&freeze, json!({"frozen_on": days_ago(2)}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "already_skipped");
+    let frozen = ann
+        .post(&freeze, json!({"frozen_on": days_ago(3)}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(frozen["streak_freezes"], 0);
+    ann.post(&freeze, json!({"frozen_on": days_ago(4)}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "no_streak_freezes");
+
+    let list = ann.get(&skips).await.expect(StatusCode::OK);
+    assert_eq!(list["skips"].as_array().unwrap().len(), 4);
+
+    // Deleting a freeze refunds it
+    ann.delete(&format!("{skips}/{}", days_ago(3)))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.delete(&format!("{skips}/{}", days_ago(3)))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+    let frozen = ann
+        .post(&freeze, json!({"frozen_on": days_ago(4)}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(frozen["streak_freezes"], 0);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn pausing_hides_a_habit_until_resumed(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann.create("/habits", "habit", json!({"name": "Gym"})).await;
+
+    ann.post(
+        &format!("/habits/{id}/pause"),
+        json!({"until": days_ago(1)}),
+    )
+    .await
+    .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    let paused = ann
+        .post(
+            &format!("/habits/{id}/pause"),
+            json!({"until": days_ahead(7)}),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(paused["habit"]["paused_until"], days_ahead(7));
+    assert!(habit_ids(&ann, "/habits/due-today").await.is_empty());
+
+    let resumed = ann
+        .post(&format!("/habits/{id}/resume"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert!(resumed["habit"]["paused_until"].is_null());
+    assert_eq!(habit_ids(&ann, "/habits/due-today").await, [id.as_str()]);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn a_pause_in_the_middle_of_a_streak_keeps_it(pool: PgPool) {
+    let app = TestApp::new(pool.clone());
+    let ann = app.user("ann").await;
+    let id = ann.create("/habits", "habit", json!({"name": "Gym"})).await;
+    let dates: Vec<_> = [6, 5, 4, 1].into_iter().map(days_ago).collect();
+    ann.post(
+        &format!("/habits/{id}/completions"),
+        json!({"dates": dates}),
+    )
+    .await
+    .expect(StatusCode::OK);
+    ann.post(&format!("/habits/{id}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    let stats = format!("/habits/{id}/stats");
+    let before = ann.get(&stats).await.expect(StatusCode::OK);
+    assert_eq!(before["stats"]["current_streak"], 2);
+
+    // Pauses only start today through the API, so record a past one directly
+    sqlx::query(
+        "INSERT INTO habit_pauses (habit_id, starts_on, ends_on)
+         VALUES ($1::uuid, $2::date, $3::date)",
+    )
+    .bind(&id)
+    .bind(days_ago(3))
+    .bind(days_ago(2))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let after = ann.get(&stats).await.expect(StatusCode::OK);
+    assert_eq!(after["stats"]["current_streak"], 5);
+    assert_eq!(after["stats"]["longest_streak"], 5);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn purging_removes_deleted_habits_but_not_archived_ones(pool: PgPool) {
+    let app = TestApp::new(pool.clone());
+    let ann = app.user("ann").await;
+    let kept = ann
+        .create("/habits", "habit", json!({"name": "Read"}))
+        .await;
+    let gone = ann.create("/habits", "habit", json!({"name": "Gym"})).await;
+    for id in [&kept, &gone] {
+        ann.post(&format!("/habits/{id}/complete"), json!({}))
+            .await
+            .expect(StatusCode::OK);
+    }
+    ann.post(&format!("/habits/{kept}/archive"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    ann.delete(&format!("/habits/{gone}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.delete(&format!("/habits/{gone}"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+    assert_eq!(
+        habit_ids(&ann, "/habits?include_archived=true").await,
+        [kept.as_str()]
+    );
+
+    sqlx::query(
+        "UPDATE habits
+         SET archived_at = archived_at - INTERVAL '100 days',
+             deleted_at = deleted_at - INTERVAL '100 days'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    commands::purge_deleted(&pool, 90, false).await.unwrap();
+
+    let remaining: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM habits")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, [kept.parse::<Uuid>().unwrap()]);
+    let stats = ann
+        .get(&format!("/habits/{kept}/stats"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(stats["stats"]["total_completions"], 1);
+}
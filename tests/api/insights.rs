@@ -0,0 +1,114 @@
+use axum::http::StatusCode;
+use projekt::db::MIGRATOR;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::common::{TestApp, days_ago, days_ahead};
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn at_risk_lists_overdue_and_piled_up_work(pool: PgPool) {
+    let app = TestApp::new(pool.clone());
+    let ann = app.user("ann").await;
+
+    ann.create(
+        "/goals",
+        "goal",
+        json!({"title": "Comfortable", "deadline": days_ahead(60)}),
+    )
+    .await;
+    let late_goal = ann
+        .create(
+            "/goals",
+            "goal",
+            json!({"title": "Missed", "deadline": days_ahead(1)}),
+        )
+        .await;
+    // Three tasks due the same day for a user with no completion history
+    for title in ["One", "Two", "Three"] {
+        ann.create(
+            "/tasks",
+            "task",
+            json!({"title": title, "due_date": days_ahead(2)}),
+        )
+        .await;
+    }
+    let overdue = ann
+        .create("/tasks", "task", json!({"title": "Forgotten"}))
+        .await;
+    // The API refuses past dates, so age the goal and task directly
+    sqlx::query("UPDATE goals SET deadline = $2::date WHERE id = $1::uuid")
+        .bind(&late_goal)
+        .bind(days_ago(3))
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE tasks SET due_date = $2::date WHERE id = $1::uuid")
+        .bind(&overdue)
+        .bind(days_ago(5))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let risk = ann.get("/insights/at-risk").await.expect(StatusCode::OK);
+    let goals = risk["goals"].as_array().unwrap();
+    assert_eq!(goals.len(), 1);
+    assert_eq!(goals[0]["id"], late_goal.as_str());
+    assert_eq!(goals[0]["forecast"]["risk"], "overdue");
+
+    let tasks = risk["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 4);
+    let forgotten = tasks.iter().find(|t| t["id"] == overdue.as_str()).unwrap();
+    assert_eq!(forgotten["risk"], "overdue");
+    assert!(
+        tasks
+            .iter()
+            .filter(|t| t["id"] != overdue.as_str())
+            .all(|t| t["risk"] == "at_risk" && t["tasks_due_same_day"] == 3)
+    );
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn estimates_compare_estimated_and_actual_time(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let empty = ann.get("/insights/estimates").await.expect(StatusCode::OK);
+    assert_eq!(empty["estimates"]["tasks"], 0);
+    assert!(empty["estimates"]["ratio"].is_null());
+
+    for (estimated, actual) in [(60, 90), (60, 30)] {
+        let id = ann
+            .create(
+                "/tasks",
+                "task",
+                json!({"title": "Estimated", "estimated_minutes": estimated}),
+            )
+            .await;
+        ann.post(
+            &format!("/tasks/{id}/time-entries"),
+            json!({"minutes": actual}),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+        ann.post(&format!("/tasks/{id}/complete"), json!({}))
+            .await
+            .expect(StatusCode::OK);
+    }
+    // Open tasks don't count yet
+    ann.create(
+        "/tasks",
+        "task",
+        json!({"title": "Open", "estimated_minutes": 10}),
+    )
+    .await;
+
+    let estimates = ann.get("/insights/estimates").await.expect(StatusCode::OK);
+    let estimates = &estimates["estimates"];
+    assert_eq!(estimates["tasks"], 2);
+    assert_eq!(estimates["estimated_minutes"], 120);
+    assert_eq!(estimates["actual_minutes"], 120.0);
+    assert_eq!(estimates["ratio"], 1.0);
+    assert_eq!(estimates["underestimated"], 1);
+    assert_eq!(estimates["overestimated"], 1);
+    assert_eq!(estimates["by_month"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,311 @@
+// Every id-addressed route must answer another user's resources with the same
+// 404 as a missing one, and leave them untouched.
+use axum::http::{Method, StatusCode};
+use projekt::db::MIGRATOR;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+use crate::common::{Client, TestApp, days_ago, days_ahead, today};
+
+async fn assert_hidden(intruder: &Client, method: Method, uri: &str, body: Option<Value>) {
+    let response = intruder.request(method.clone(), uri, body).await;
+    assert_eq!(
+        response.status,
+        StatusCode::NOT_FOUND,
+        "{method} {uri} leaked: {}",
+        response.text
+    );
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn tasks_and_goals(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let bob = app.user("bob").await;
+
+    let goal = ann
+        .create("/goals", "goal", json!({"title": "Ann's goal"}))
+        .await;
+    let task = ann
+        .create(
+            "/tasks",
+            "task",
+            json!({"title": "Ann's task", "goal_id": goal}),
+        )
+        .await;
+    let entry = ann
+        .create(
+            &format!("/tasks/{task}/time-entries"),
+            "time_entry",
+            json!({"minutes": 20}),
+        )
+        .await;
+
+    let task_uri = format!("/tasks/{task}");
+    let entries_uri = format!("{task_uri}/time-entries");
+    let goal_uri = format!("/goals/{goal}");
+    let probes = [
+        (Method::GET, task_uri.clone(), None),
+        (
+            Method::PATCH,
+            task_uri.clone(),
+            Some(json!({"title": "Bob's now"})),
+        ),
+        (
+            Method::POST,
+            format!("{task_uri}/complete"),
+            Some(json!({})),
+        ),
+        (Method::GET, entries_uri.clone(), None),
+        (
+            Method::POST,
+            entries_uri.clone(),
+            Some(json!({"minutes": 5})),
+        ),
+        (Method::DELETE, format!("{entries_uri}/{entry}"), None),
+        (
+            Method::PATCH,
+            goal_uri.clone(),
+            Some(json!({"title": "Bob's now"})),
+        ),
+        (
+            Method::POST,
+            format!("{goal_uri}/complete"),
+            Some(json!({})),
+        ),
+        (Method::DELETE, task_uri.clone(), None),
+        (Method::DELETE, goal_uri.clone(), None),
+    ];
+    for (method, uri, body) in probes {
+        assert_hidden(&bob, method, &uri, body).await;
+    }
+    // Nor can Bob hang his own tasks off Ann's goal
+    assert_hidden(
+        &bob,
+        Method::POST,
+        "/tasks",
+        Some(json!({"title": "Sneaky", "goal_id": goal})),
+    )
+    .await;
+    let own = bob
+        .create("/tasks", "task", json!({"title": "Bob's task"}))
+        .await;
+    assert_hidden(
+        &bob,
+        Method::PATCH,
+        &format!("/tasks/{own}"),
+        Some(json!({"goal_id": goal})),
+    )
+    .await;
+
+    let tasks = bob.get("/tasks").await.expect(StatusCode::OK);
+    assert_eq!(tasks["tasks"].as_array().unwrap().len(), 1);
+    let goals = bob.get("/goals").await.expect(StatusCode::OK);
+    assert_eq!(goals["goals"], json!([]));
+    let risk = bob.get("/insights/at-risk").await.expect(StatusCode::OK);
+    assert_eq!(risk["tasks"], json!([]));
+
+    // Ann's data is as she left it
+    let detail = ann.get(&task_uri).await.expect(StatusCode::OK);
+    assert_eq!(detail["task"]["title"], "Ann's task");
+    assert_eq!(detail["task"]["completed"], false);
+    assert_eq!(detail["task"]["effort"]["manual_minutes"], 20);
+    let goals = ann.get("/goals").await.expect(StatusCode::OK);
+    assert_eq!(goals["goals"][0]["title"], "Ann's goal");
+    assert_eq!(goals["goals"][0]["status"], "active");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn habits(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let bob = app.user("bob").await;
+
+    let habit = ann
+        .create("/habits", "habit", json!({"name": "Ann's habit"}))
+        .await;
+    let uri = format!("/habits/{habit}");
+    ann.post(&format!("{uri}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    ann.post(
+        &format!("{uri}/skips"),
+        json!({"skipped_on": days_ahead(1)}),
+    )
+    .await
+    .expect(StatusCode::CREATED);
+
+    let probes = [
+        (
+            Method::PATCH,
+            uri.clone(),
+            Some(json!({"name": "Bob's now"})),
+        ),
+        (
+            Method::POST,
+            format!("{uri}/complete"),
+            Some(json!({"completed_on": days_ago(2)})),
+        ),
+        (Method::GET, format!("{uri}/stats"), None),
+        (Method::GET, format!("{uri}/completions"), None),
+        (
+            Method::POST,
+            format!("{uri}/completions"),
+            Some(json!({"dates": [days_ago(3)]})),
+        ),
+        (
+            Method::DELETE,
+            format!("{uri}/completions/{}", today()),
+            None,
+        ),
+        (Method::GET, format!("{uri}/skips"), None),
+        (
+            Method::POST,
+            format!("{uri}/skips"),
+            Some(json!({"skipped_on": days_ahead(4)})),
+        ),
+        (
+            Method::DELETE,
+            format!("{uri}/skips/{}", days_ahead(1)),
+            None,
+        ),
+        (
+            Method::POST,
+            format!("{uri}/freeze"),
+            Some(json!({"frozen_on": days_ago(5)})),
+        ),
+        (Method::POST, format!("{uri}/archive"), Some(json!({}))),
+        (Method::POST, format!("{uri}/unarchive"), Some(json!({}))),
+        (
+            Method::POST,
+            format!("{uri}/pause"),
+            Some(json!({"until": days_ahead(3)})),
+        ),
+        (Method::POST, format!("{uri}/resume"), Some(json!({}))),
+        (Method::DELETE, uri.clone(), None),
+    ];
+    for (method, uri, body) in probes {
+        assert_hidden(&bob, method, &uri, body).await;
+    }
+
+    let habits = bob
+        .get("/habits?include_archived=true")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(habits["habits"], json!([]));
+    let due = bob.get("/habits/due-today").await.expect(StatusCode::OK);
+    assert_eq!(due["habits"], json!([]));
+
+    let habits = ann.get("/habits").await.expect(StatusCode::OK);
+    let own = &habits["habits"][0];
+    assert_eq!(own["name"], "Ann's habit");
+    assert!(own["paused_until"].is_null());
+    assert_eq!(own["stats"]["total_completions"], 1);
+    let skips = ann
+        .get(&format!("{uri}/skips"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(skips["skips"].as_array().unwrap().len(), 1);
+    // Bob's failed freeze didn't cost him one either
+    let own = bob
+        .create("/habits", "habit", json!({"name": "Bob's habit"}))
+        .await;
+    let frozen = bob
+        .post(&format!("/habits/{own}/freeze"), json!({}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(frozen["streak_freezes"], 1);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn pomodoro_sessions(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let bob = app.user("bob").await;
+
+    let task = ann
+        .create("/tasks", "task", json!({"title": "Ann's task"}))
+        .await;
+    let session = ann
+        .create("/pomodoro/start", "session", json!({"task_id": task}))
+        .await;
+    let uri = format!("/pomodoro/{session}");
+
+    let probes = [
+        (Method::GET, uri.clone(), None),
+        (Method::POST, format!("{uri}/pause"), Some(json!({}))),
+        (Method::POST, format!("{uri}/resume"), Some(json!({}))),
+        (Method::POST, format!("{uri}/end"), Some(json!({}))),
+        (Method::GET, format!("{uri}/interruptions"), None),
+        (
+            Method::POST,
+            format!("{uri}/interruptions"),
+            Some(json!({"kind": "internal"})),
+        ),
+        (Method::DELETE, uri.clone(), None),
+    ];
+    for (method, uri, body) in probes {
+        assert_hidden(&bob, method, &uri, body).await;
+    }
+    // Bob can't time his session against Ann's task, and Ann's open session
+    // doesn't block his
+    assert_hidden(
+        &bob,
+        Method::POST,
+        "/pomodoro/start",
+        Some(json!({"task_id": task})),
+    )
+    .await;
+    bob.post("/pomodoro/start", json!({}))
+        .await
+        .expect(StatusCode::CREATED);
+
+    let sessions = bob.get("/pomodoro").await.expect(StatusCode::OK);
+    assert_eq!(sessions["sessions"].as_array().unwrap().len(), 1);
+    let current = bob.get("/pomodoro/current").await.expect(StatusCode::OK);
+    assert_ne!(current["session"]["id"], session.as_str());
+
+    let own = ann.get(&uri).await.expect(StatusCode::OK);
+    assert_eq!(own["session"]["status"], "running");
+    assert!(own["session"]["ended_at"].is_null());
+    assert_eq!(own["session"]["interruptions"], json!([]));
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn vacations_and_settings(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let bob = app.user("bob").await;
+
+    let vacation = ann
+        .create(
+            "/vacations",
+            "vacation",
+            json!({"starts_on": days_ahead(1), "ends_on": days_ahead(2)}),
+        )
+        .await;
+    assert_hidden(
+        &bob,
+        Method::DELETE,
+        &format!("/vacations/{vacation}"),
+        None,
+    )
+    .await;
+    let vacations = bob.get("/vacations").await.expect(StatusCode::OK);
+    assert_eq!(vacations["vacations"], json!([]));
+    let vacations = ann.get("/vacations").await.expect(StatusCode::OK);
+    assert_eq!(vacations["vacations"].as_array().unwrap().len(), 1);
+
+    ann.put("/pomodoro/settings", json!({"focus_minutes": 50}))
+        .await
+        .expect(StatusCode::OK);
+    let settings = bob.get("/pomodoro/settings").await.expect(StatusCode::OK);
+    assert_eq!(settings["settings"]["focus_minutes"], 25);
+    assert_eq!(settings["settings"]["user_id"], bob.user_id.to_string());
+
+    bob.patch("/auth/me", json!({"timezone": "Asia/Tokyo"}))
+        .await
+        .expect(StatusCode::OK);
+    let me = ann.get("/auth/me").await.expect(StatusCode::OK);
+    assert_eq!(me["user"]["timezone"], "UTC");
+}
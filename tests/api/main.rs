@@ -0,0 +1,13 @@
+// Runs against a real Postgres: every #[sqlx::test] gets a freshly created
+// and migrated database from DATABASE_URL's server.
+mod common;
+
+mod auth;
+mod goals;
+mod habits;
+mod insights;
+mod isolation;
+mod pomodoro;
+mod service;
+mod tasks;
+mod vacations;
@@ -0,0 +1,315 @@
+use std::time::Duration;
+
+use axum::http::{StatusCode, header};
+use projekt::db::MIGRATOR;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tokio_stream::StreamExt;
+
+use crate::common::{TestApp, today};
+
+// Moves a session's start back so it has run for `minutes`
+async fn age_session(pool: &PgPool, id: &str, minutes: i32) {
+    sqlx::query(
+        "UPDATE pomodoro_sessions
+         SET started_at = started_at - make_interval(mins => $2)
+         WHERE id = $1::uuid",
+    )
+    .bind(id)
+    .bind(minutes)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+fn session_id(body: &Value) -> String {
+    body["session"]["id"].as_str().unwrap().to_string()
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn one_open_session_at_a_time(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let current = ann.get("/pomodoro/current").await.expect(StatusCode::OK);
+    assert!(current["session"].is_null());
+
+    let started = ann
+        .post("/pomodoro/start", json!({}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(started["session"]["session_type"], "focus");
+    assert_eq!(started["session"]["duration_minutes"], 25);
+    assert_eq!(started["session"]["status"], "running");
+    let first = session_id(&started);
+
+    let conflict = ann
+        .post("/pomodoro/start", json!({"session_type": "short_break"}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "session_active");
+    assert_eq!(conflict["session"]["id"], first.as_str());
+
+    let replaced = ann
+        .post(
+            "/pomodoro/start?replace=true",
+            json!({"session_type": "short_break", "duration_minutes": 10}),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let second = session_id(&replaced);
+    let old = ann
+        .get(&format!("/pomodoro/{first}"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(old["session"]["status"], "interrupted");
+
+    let current = ann.get("/pomodoro/current").await.expect(StatusCode::OK);
+    assert_eq!(current["session"]["id"], second.as_str());
+    assert_eq!(current["session"]["remaining_seconds"], 600);
+    let list = ann.get("/pomodoro").await.expect(StatusCode::OK);
+    assert_eq!(list["sessions"].as_array().unwrap().len(), 2);
+
+    ann.post("/pomodoro/start", json!({"duration_minutes": 0}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    ann.post(
+        "/pomodoro/start?replace=true",
+        json!({"task_id": uuid::Uuid::new_v4()}),
+    )
+    .await
+    .expect_problem(StatusCode::NOT_FOUND, "not_found");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn pausing_resuming_and_ending(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann.create("/pomodoro/start", "session", json!({})).await;
+
+    ann.post(&format!("/pomodoro/{id}/resume"), json!({}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "invalid_transition");
+    let paused = ann
+        .post(&format!("/pomodoro/{id}/pause"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(paused["session"]["status"], "paused");
+    ann.post(&format!("/pomodoro/{id}/pause"), json!({}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "invalid_transition");
+    let resumed = ann
+        .post(&format!("/pomodoro/{id}/resume"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(resumed["session"]["status"], "running");
+
+    // Ended well before its 25 minutes were up
+    let ended = ann
+        .post(
+            &format!("/pomodoro/{id}/end"),
+            json!({"notes": "phone call"}),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(ended["session"]["status"], "interrupted");
+    assert_eq!(ended["session"]["notes"], "phone call");
+    assert!(ended["session"]["ended_at"].is_string());
+    assert!(ended["next"].is_null());
+
+    // Ending again only updates the notes
+    let again = ann
+        .post(
+            &format!("/pomodoro/{id}/end"),
+            json!({"notes": "two calls"}),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(again["session"]["notes"], "two calls");
+    assert_eq!(again["session"]["ended_at"], ended["session"]["ended_at"]);
+    ann.post(&format!("/pomodoro/{id}/pause"), json!({}))
+        .await
+        .expect_problem(StatusCode::CONFLICT, "invalid_transition");
+
+    ann.delete(&format!("/pomodoro/{id}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.get(&format!("/pomodoro/{id}"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn completed_sessions_follow_the_cycle(pool: PgPool) {
+    let app = TestApp::new(pool.clone());
+    let ann = app.user("ann").await;
+
+    let settings = ann.get("/pomodoro/settings").await.expect(StatusCode::OK);
+    assert_eq!(settings["settings"]["focus_minutes"], 25);
+    let settings = ann
+        .put(
+            "/pomodoro/settings",
+            json!({"focus_minutes": 1, "short_break_minutes": 2, "auto_start_next": true}),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(settings["settings"]["focus_minutes"], 1);
+    assert_eq!(settings["settings"]["long_break_minutes"], 15);
+    ann.put("/pomodoro/settings", json!({"long_break_every": 0}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    let task = ann
+        .create("/tasks", "task", json!({"title": "Deep work"}))
+        .await;
+    let focus = ann
+        .post("/pomodoro/next", json!({"task_id": task}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(focus["session"]["session_type"], "focus");
+    assert_eq!(focus["session"]["duration_minutes"], 1);
+    let id = session_id(&focus);
+    age_session(&pool, &id, 2).await;
+
+    let ended = ann
+        .post(&format!("/pomodoro/{id}/end"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(ended["session"]["status"], "completed");
+    assert_eq!(ended["next"]["session_type"], "short_break");
+    assert_eq!(ended["next"]["duration_minutes"], 2);
+
+    let stats = ann.get("/pomodoro/stats").await.expect(StatusCode::OK);
+    let stats = &stats["stats"];
+    assert_eq!(stats["to"], today().to_string());
+    assert_eq!(stats["sessions"], 1);
+    assert_eq!(stats["completed"], 1);
+    assert_eq!(stats["by_task"][0]["key"], task.as_str());
+    let by_tag = ann
+        .get("/pomodoro/stats?group_by=tag")
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(by_tag["stats"]["group_by"], "tag");
+    ann.get(&format!("/pomodoro/stats?from={}&to=2020-01-01", today()))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    // Deleting the task keeps its sessions, unlinked
+    ann.delete(&format!("/tasks/{task}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    let session = ann
+        .get(&format!("/pomodoro/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    assert!(session["session"]["task_id"].is_null());
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn interruptions(pool: PgPool) {
+    let app = TestApp::new(pool.clone());
+    let ann = app.user("ann").await;
+    let id = ann.create("/pomodoro/start", "session", json!({})).await;
+    age_session(&pool, &id, 10).await;
+    let interruptions = format!("/pomodoro/{id}/interruptions");
+
+    let logged = ann
+        .post(
+            &interruptions,
+            json!({"kind": "external", "note": "doorbell"}),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(logged["interruption"]["kind"], "external");
+    let earlier = chrono::Utc::now() - chrono::Duration::minutes(5);
+    ann.post(
+        &interruptions,
+        json!({"kind": "internal", "occurred_at": earlier}),
+    )
+    .await
+    .expect(StatusCode::CREATED);
+    let before_start = chrono::Utc::now() - chrono::Duration::hours(1);
+    ann.post(
+        &interruptions,
+        json!({"kind": "internal", "occurred_at": before_start}),
+    )
+    .await
+    .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    let list = ann.get(&interruptions).await.expect(StatusCode::OK);
+    let list = list["interruptions"].as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["kind"], "internal");
+
+    let session = ann
+        .get(&format!("/pomodoro/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(
+        session["session"]["interruptions"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn stream_sends_a_snapshot_then_events(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let response = ann.open("/pomodoro/stream").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    let mut body = response.into_body().into_data_stream();
+    let mut next_frame = async || {
+        let frame = tokio::time::timeout(Duration::from_secs(5), body.next())
+            .await
+            .expect("no event within 5s")
+            .unwrap()
+            .unwrap();
+        String::from_utf8(frame.to_vec()).unwrap()
+    };
+
+    assert!(next_frame().await.starts_with("event: snapshot"));
+    let id = ann.create("/pomodoro/start", "session", json!({})).await;
+    let started = next_frame().await;
+    assert!(started.starts_with("event: started"), "{started}");
+    assert!(started.contains(&id));
+}
+
+#[tokio::test]
+async fn stream_runs_on_the_in_memory_repos() {
+    let app = TestApp::in_memory();
+    let ann = app.user("ann").await;
+    let id = ann.create("/pomodoro/start", "session", json!({})).await;
+
+    let response = ann.open("/pomodoro/stream").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+    let frame = tokio::time::timeout(Duration::from_secs(5), body.next())
+        .await
+        .expect("no event within 5s")
+        .unwrap()
+        .unwrap();
+    let snapshot = String::from_utf8(frame.to_vec()).unwrap();
+    assert!(snapshot.starts_with("event: snapshot"), "{snapshot}");
+    assert!(snapshot.contains(&id));
+
+    ann.post(&format!("/pomodoro/{id}/pause"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    let frame = tokio::time::timeout(Duration::from_secs(5), body.next())
+        .await
+        .expect("no event within 5s")
+        .unwrap()
+        .unwrap();
+    assert!(
+        String::from_utf8(frame.to_vec())
+            .unwrap()
+            .starts_with("event: paused")
+    );
+}
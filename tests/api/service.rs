@@ -0,0 +1,91 @@
+use axum::http::{StatusCode, header};
+use projekt::{config::Config, db::MIGRATOR};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::common::TestApp;
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn health_probes(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let anonymous = app.anonymous();
+
+    let health = anonymous.get("/healthz").await.expect(StatusCode::OK);
+    assert_eq!(health["status"], "ok");
+    let ready = anonymous.get("/readyz").await.expect(StatusCode::OK);
+    assert_eq!(ready["status"], "ready");
+    assert_eq!(ready["checks"]["migrations"], "ok");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn api_docs(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let anonymous = app.anonymous();
+
+    let spec = anonymous.get("/openapi.json").await.expect(StatusCode::OK);
+    assert_eq!(spec["openapi"], "3.1.0");
+    assert!(spec["paths"]["/tasks/{id}"]["patch"].is_object());
+    assert_eq!(
+        spec["components"]["schemas"]["Priority"]["enum"],
+        json!(["low", "medium", "high"])
+    );
+
+    let docs = anonymous.get("/docs").await;
+    assert_eq!(docs.status, StatusCode::OK);
+    assert!(docs.text.contains("/openapi.json"));
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn html_views(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let anonymous = app.anonymous();
+
+    for uri in ["/", "/auth/login", "/auth/register"] {
+        let page = anonymous.get(uri).await;
+        assert_eq!(page.status, StatusCode::OK, "{uri}");
+        assert!(
+            page.headers[header::CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .starts_with("text/html"),
+            "{uri}"
+        );
+    }
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn metrics_count_requests_by_route(pool: PgPool) {
+    // By default /metrics has a listener of its own, away from the API
+    let app = TestApp::new(pool.clone());
+    let metrics = app.anonymous().get("/metrics").await;
+    assert_eq!(metrics.status, StatusCode::NOT_FOUND);
+
+    let mut config = Config::default();
+    config.metrics.port = 0;
+    let app = TestApp::with_config(pool, &config);
+    let ann = app.user("ann").await;
+    ann.get("/tasks").await.expect(StatusCode::OK);
+
+    let metrics = app.anonymous().get("/metrics").await;
+    assert_eq!(metrics.status, StatusCode::OK);
+    assert!(metrics.text.contains("http_requests_total{"));
+    assert!(metrics.text.contains(r#"route="/tasks""#));
+    assert!(metrics.text.contains("db_pool_max_connections"));
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn responses_carry_a_request_id(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let anonymous = app.anonymous();
+
+    let assigned = anonymous.get("/healthz").await;
+    let id = assigned.headers["x-request-id"].to_str().unwrap();
+    assert!(uuid::Uuid::parse_str(id).is_ok());
+
+    // Problem documents carry the id too
+    let problem = anonymous
+        .get("/tasks")
+        .await
+        .expect_problem(StatusCode::UNAUTHORIZED, "unauthorized");
+    assert!(problem["request_id"].is_string());
+}
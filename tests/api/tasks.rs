@@ -0,0 +1,195 @@
+use axum::http::StatusCode;
+use projekt::db::MIGRATOR;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::common::{TestApp, days_ago, days_ahead, today};
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn task_crud(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let created = ann
+        .post(
+            "/tasks",
+            json!({
+                "title": "Write report",
+                "priority": "high",
+                "due_date": days_ahead(3),
+                "tags": ["Work", "work ", "writing"],
+                "estimated_minutes": 90,
+            }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    let task = &created["task"];
+    assert_eq!(task["title"], "Write report");
+    assert_eq!(task["priority"], "high");
+    assert_eq!(task["completed"], false);
+    assert_eq!(task["tags"], json!(["work", "writing"]));
+    let id = task["id"].as_str().unwrap();
+
+    let list = ann.get("/tasks").await.expect(StatusCode::OK);
+    assert_eq!(list["tasks"].as_array().unwrap().len(), 1);
+
+    let detail = ann
+        .get(&format!("/tasks/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(detail["task"]["id"], id);
+    assert_eq!(detail["task"]["effort"]["estimated_minutes"], 90);
+
+    let updated = ann
+        .patch(
+            &format!("/tasks/{id}"),
+            json!({"title": "Write the report", "priority": "low"}),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(updated["task"]["title"], "Write the report");
+    assert_eq!(updated["task"]["priority"], "low");
+    assert_eq!(updated["task"]["due_date"], days_ahead(3));
+
+    ann.delete(&format!("/tasks/{id}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.get(&format!("/tasks/{id}"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+    ann.delete(&format!("/tasks/{id}"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn task_validation(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    ann.post("/tasks", json!({"title": "   "}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    ann.post("/tasks", json!({"title": "Late", "due_date": days_ago(2)}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    ann.post("/tasks", json!({"title": "Odd", "priority": "urgent"}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    ann.post(
+        "/tasks",
+        json!({"title": "Orphan", "goal_id": uuid::Uuid::new_v4()}),
+    )
+    .await
+    .expect_problem(StatusCode::NOT_FOUND, "not_found");
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn linking_and_unlinking_a_goal(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let goal = ann
+        .create("/goals", "goal", json!({"title": "Launch"}))
+        .await;
+    let id = ann
+        .create("/tasks", "task", json!({"title": "Plan", "goal_id": goal}))
+        .await;
+
+    // Leaving goal_id out keeps the link, null removes it
+    let renamed = ann
+        .patch(&format!("/tasks/{id}"), json!({"title": "Plan it"}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(renamed["task"]["goal_id"], goal);
+    let unlinked = ann
+        .patch(&format!("/tasks/{id}"), json!({"goal_id": null}))
+        .await
+        .expect(StatusCode::OK);
+    assert!(unlinked["task"]["goal_id"].is_null());
+    let goals = ann.get("/goals").await.expect(StatusCode::OK);
+    assert_eq!(goals["goals"][0]["forecast"]["total_tasks"], 0);
+
+    let relinked = ann
+        .patch(&format!("/tasks/{id}"), json!({"goal_id": goal}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(relinked["task"]["goal_id"], goal);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn completing_keeps_the_first_completion_time(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann.create("/tasks", "task", json!({"title": "Ship"})).await;
+
+    let first = ann
+        .post(&format!("/tasks/{id}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(first["task"]["completed"], true);
+    let completed_at = first["task"]["completed_at"].clone();
+    assert!(completed_at.is_string());
+
+    let again = ann
+        .post(&format!("/tasks/{id}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(again["task"]["completed_at"], completed_at);
+
+    let reopened = ann
+        .patch(&format!("/tasks/{id}"), json!({"completed": false}))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(reopened["task"]["completed"], false);
+    assert!(reopened["task"]["completed_at"].is_null());
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn time_entries_add_up_in_the_effort(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann
+        .create(
+            "/tasks",
+            "task",
+            json!({"title": "Refactor", "estimated_minutes": 60}),
+        )
+        .await;
+    let entries = format!("/tasks/{id}/time-entries");
+
+    let entry = ann
+        .post(&entries, json!({"minutes": 45, "note": "first pass"}))
+        .await
+        .expect(StatusCode::CREATED);
+    assert_eq!(entry["time_entry"]["spent_on"], today().to_string());
+    let entry_id = entry["time_entry"]["id"].as_str().unwrap().to_string();
+    ann.post(&entries, json!({"minutes": 30, "spent_on": days_ago(2)}))
+        .await
+        .expect(StatusCode::CREATED);
+    ann.post(&entries, json!({"minutes": 0}))
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    let list = ann.get(&entries).await.expect(StatusCode::OK);
+    let list = list["time_entries"].as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["spent_on"], days_ago(2));
+
+    let detail = ann
+        .get(&format!("/tasks/{id}"))
+        .await
+        .expect(StatusCode::OK);
+    let effort = &detail["task"]["effort"];
+    assert_eq!(effort["manual_minutes"], 75);
+    assert_eq!(effort["actual_minutes"], 75.0);
+    assert_eq!(effort["estimate_ratio"], 1.25);
+
+    ann.delete(&format!("{entries}/{entry_id}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.delete(&format!("{entries}/{entry_id}"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+    let list = ann.get(&entries).await.expect(StatusCode::OK);
+    assert_eq!(list["time_entries"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,88 @@
+use axum::http::StatusCode;
+use projekt::db::MIGRATOR;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::common::{TestApp, days_ago, days_ahead, today};
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn vacation_crud(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+
+    let later = ann
+        .create(
+            "/vacations",
+            "vacation",
+            json!({"starts_on": days_ahead(20), "ends_on": days_ahead(25)}),
+        )
+        .await;
+    ann.create(
+        "/vacations",
+        "vacation",
+        json!({"starts_on": days_ahead(5), "ends_on": days_ahead(5), "note": "Long weekend"}),
+    )
+    .await;
+    ann.post(
+        "/vacations",
+        json!({"starts_on": days_ahead(5), "ends_on": days_ahead(4)}),
+    )
+    .await
+    .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+
+    let list = ann.get("/vacations").await.expect(StatusCode::OK);
+    let list = list["vacations"].as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["note"], "Long weekend");
+
+    ann.delete(&format!("/vacations/{later}"))
+        .await
+        .expect(StatusCode::NO_CONTENT);
+    ann.delete(&format!("/vacations/{later}"))
+        .await
+        .expect_problem(StatusCode::NOT_FOUND, "not_found");
+    let list = ann.get("/vacations").await.expect(StatusCode::OK);
+    assert_eq!(list["vacations"].as_array().unwrap().len(), 1);
+}
+
+#[sqlx::test(migrator = "MIGRATOR")]
+async fn a_vacation_cannot_excuse_missed_days(pool: PgPool) {
+    let app = TestApp::new(pool);
+    let ann = app.user("ann").await;
+    let id = ann
+        .create("/habits", "habit", json!({"name": "Read"}))
+        .await;
+    ann.post(
+        &format!("/habits/{id}/completions"),
+        json!({"dates": [days_ago(4), days_ago(3)]}),
+    )
+    .await
+    .expect(StatusCode::OK);
+    ann.post(&format!("/habits/{id}/complete"), json!({}))
+        .await
+        .expect(StatusCode::OK);
+
+    let refused = ann
+        .post(
+            "/vacations",
+            json!({"starts_on": days_ago(2), "ends_on": days_ago(1)}),
+        )
+        .await
+        .expect_problem(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed");
+    assert_eq!(refused["errors"][0]["field"], "starts_on");
+    let list = ann.get("/vacations").await.expect(StatusCode::OK);
+    assert_eq!(list["vacations"], json!([]));
+    let stats = ann
+        .get(&format!("/habits/{id}/stats"))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(stats["stats"]["current_streak"], 1);
+
+    // Starting today is fine
+    ann.create(
+        "/vacations",
+        "vacation",
+        json!({"starts_on": today(), "ends_on": days_ahead(2)}),
+    )
+    .await;
+}
@@ -0,0 +1,158 @@
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use projekt::{models::goal::GoalStatus, repo::Repos, timezone::today_in};
+use serde_json::json;
+
+use crate::{body, user};
+
+conformance!(progress, delete_unlinks_tasks, scoped_to_owner);
+
+async fn progress(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let goal = repos
+        .goals
+        .create(ann.id, body(json!({"title": "Ship v2"})))
+        .await
+        .unwrap();
+    let other = repos
+        .goals
+        .create(ann.id, body(json!({"title": "Learn Rust"})))
+        .await
+        .unwrap();
+    for title in ["Design", "Build", "Release"] {
+        repos
+            .tasks
+            .create(ann.id, body(json!({"title": title, "goal_id": goal.id})))
+            .await
+            .unwrap();
+    }
+    let task = repos.tasks.list(ann.id).await.unwrap()[0].clone();
+    repos.tasks.complete(ann.id, task.id).await.unwrap();
+
+    let recent_after = Utc::now().date_naive() - Duration::days(28);
+    let goals = repos
+        .goals
+        .list_with_progress(ann.id, Tz::UTC, recent_after)
+        .await
+        .unwrap();
+    let ids: Vec<_> = goals.iter().map(|g| g.goal.id).collect();
+    assert_eq!(ids, [goal.id, other.id]);
+    assert_eq!(goals[0].total_tasks, 3);
+    assert_eq!(goals[0].completed_tasks, 1);
+    assert_eq!(goals[0].recent_completions, 1);
+    assert_eq!(goals[1].total_tasks, 0);
+
+    // A completion today isn't after today, where today is the user's. These
+    // two zones are a day apart at any hour, so UTC dates can't pass for both.
+    for tz in [Tz::Pacific__Kiritimati, Tz::Pacific__Pago_Pago] {
+        let today = today_in(tz);
+        let goals = repos
+            .goals
+            .list_with_progress(ann.id, tz, today)
+            .await
+            .unwrap();
+        assert_eq!(goals[0].recent_completions, 0);
+        let goals = repos
+            .goals
+            .list_with_progress(ann.id, tz, today - Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(goals[0].recent_completions, 1);
+    }
+
+    let completed = repos
+        .goals
+        .complete(ann.id, goal.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(completed.status, GoalStatus::Completed);
+}
+
+async fn delete_unlinks_tasks(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let goal = repos
+        .goals
+        .create(ann.id, body(json!({"title": "Ship v2"})))
+        .await
+        .unwrap();
+    let task = repos
+        .tasks
+        .create(ann.id, body(json!({"title": "Design", "goal_id": goal.id})))
+        .await
+        .unwrap();
+
+    // An explicit null unlinks, a missing goal_id leaves the link alone
+    let kept = repos
+        .tasks
+        .update(ann.id, task.id, body(json!({"title": "Design it"})))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(kept.task.goal_id, Some(goal.id));
+    let unlinked = repos
+        .tasks
+        .update(ann.id, task.id, body(json!({"goal_id": null})))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(unlinked.task.goal_id, None);
+    repos
+        .tasks
+        .update(ann.id, task.id, body(json!({"goal_id": goal.id})))
+        .await
+        .unwrap();
+
+    assert!(repos.goals.delete(ann.id, goal.id).await.unwrap());
+    assert!(repos.goals.find(ann.id, goal.id).await.unwrap().is_none());
+    let task = repos.tasks.find(ann.id, task.id).await.unwrap().unwrap();
+    assert_eq!(task.goal_id, None);
+}
+
+async fn scoped_to_owner(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let goal = repos
+        .goals
+        .create(ann.id, body(json!({"title": "Ship v2"})))
+        .await
+        .unwrap();
+
+    let recent_after = Utc::now().date_naive() - Duration::days(28);
+    assert!(
+        repos
+            .goals
+            .list_with_progress(bob.id, Tz::UTC, recent_after)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(repos.goals.find(bob.id, goal.id).await.unwrap().is_none());
+    assert!(
+        repos
+            .goals
+            .update(bob.id, goal.id, body(json!({"title": "Mine now"})))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repos
+            .goals
+            .complete(bob.id, goal.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(!repos.goals.delete(bob.id, goal.id).await.unwrap());
+
+    let updated = repos
+        .goals
+        .update(ann.id, goal.id, body(json!({"description": "By spring"})))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.title, "Ship v2");
+    assert_eq!(updated.description.as_deref(), Some("By spring"));
+    assert_eq!(updated.status, GoalStatus::Active);
+}
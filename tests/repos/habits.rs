@@ -0,0 +1,630 @@
+use chrono::{Duration, NaiveDate, Utc};
+use projekt::{
+    models::habit::{Frequency, HabitPause, SkipKind},
+    repo::{RepoError, Repos},
+};
+use serde_json::json;
+
+use crate::{body, user};
+
+conformance!(
+    one_completion_per_day,
+    measurements_accumulate,
+    skips_and_freezes,
+    habits_are_scoped_to_the_owner,
+    freeze_awards_once_per_period,
+    archive_and_pause,
+    pauses_are_recorded,
+    delete_cascades,
+    vacations,
+);
+
+fn day(offset: i64) -> NaiveDate {
+    Utc::now().date_naive() + Duration::days(offset)
+}
+
+async fn one_completion_per_day(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let habit = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+    assert_eq!(habit.frequency, Frequency::Daily);
+
+    repos
+        .habits
+        .add_completion(ann.id, habit.id, day(0), None, true)
+        .await
+        .unwrap();
+    let err = repos
+        .habits
+        .add_completion(ann.id, habit.id, day(0), None, true)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RepoError::Duplicate));
+    repos
+        .habits
+        .add_completion(ann.id, habit.id, day(-2), None, true)
+        .await
+        .unwrap();
+
+    let completions = repos
+        .habits
+        .completions(ann.id, habit.id, None, None)
+        .await
+        .unwrap();
+    let days: Vec<_> = completions.iter().map(|c| c.completed_on).collect();
+    assert_eq!(days, [day(-2), day(0)]);
+    let recent = repos
+        .habits
+        .completions(ann.id, habit.id, Some(day(-1)), Some(day(0)))
+        .await
+        .unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(
+        repos.habits.user_completions(ann.id).await.unwrap().len(),
+        2
+    );
+    assert!(
+        repos
+            .habits
+            .user_completions(bob.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    assert!(
+        !repos
+            .habits
+            .delete_completion(bob.id, habit.id, day(0))
+            .await
+            .unwrap()
+    );
+    assert!(
+        repos
+            .habits
+            .delete_completion(ann.id, habit.id, day(0))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repos
+            .habits
+            .delete_completion(ann.id, habit.id, day(0))
+            .await
+            .unwrap()
+    );
+}
+
+async fn measurements_accumulate(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let habit = repos
+        .habits
+        .create(
+            ann.id,
+            body(json!({"name": "Water", "unit": "l", "daily_target": 2.0})),
+        )
+        .await
+        .unwrap();
+
+    let first = repos
+        .habits
+        .add_measurement(ann.id, habit.id, day(0), 0.5, true)
+        .await
+        .unwrap()
+        .unwrap();
+    let second = repos
+        .habits
+        .add_measurement(ann.id, habit.id, day(0), 1.0, false)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(second.id, first.id);
+    assert_eq!(second.value, Some(1.5));
+    // A backdated top-up makes the whole day backdated
+    assert!(first.same_day);
+    assert!(!second.same_day);
+    assert_eq!(
+        repos
+            .habits
+            .completions(ann.id, habit.id, None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+async fn skips_and_freezes(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let habit = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+
+    assert!(
+        repos
+            .habits
+            .skip(bob.id, habit.id, day(0), None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let skip = repos
+        .habits
+        .skip(ann.id, habit.id, day(0), Some("Travelling".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(skip.kind, SkipKind::Skip));
+    let err = repos
+        .habits
+        .skip(ann.id, habit.id, day(0), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RepoError::Duplicate));
+
+    // A day that's already skipped doesn't spend a freeze
+    let err = repos
+        .habits
+        .freeze(ann.id, habit.id, day(0))
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, RepoError::Duplicate));
+    let ann_now = repos.users.find(ann.id).await.unwrap().unwrap();
+    assert_eq!(ann_now.streak_freezes, 2);
+
+    let freeze = repos
+        .habits
+        .freeze(ann.id, habit.id, day(-1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(freeze.skip.kind, SkipKind::Freeze));
+    assert_eq!(freeze.streak_freezes, 1);
+    let freeze = repos
+        .habits
+        .freeze(ann.id, habit.id, day(-2))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(freeze.streak_freezes, 0);
+    assert!(
+        repos
+            .habits
+            .freeze(ann.id, habit.id, day(-3))
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let skips = repos
+        .habits
+        .skips(ann.id, habit.id, None, None)
+        .await
+        .unwrap();
+    let days: Vec<_> = skips.iter().map(|s| s.skipped_on).collect();
+    assert_eq!(days, [day(-2), day(-1), day(0)]);
+    assert_eq!(repos.habits.user_skips(ann.id).await.unwrap().len(), 3);
+
+    // Deleting a freeze refunds it, deleting a plain skip doesn't
+    assert!(
+        !repos
+            .habits
+            .delete_skip(bob.id, habit.id, day(-1), 3)
+            .await
+            .unwrap()
+    );
+    assert!(
+        repos
+            .habits
+            .delete_skip(ann.id, habit.id, day(-1), 3)
+            .await
+            .unwrap()
+    );
+    assert!(
+        repos
+            .habits
+            .delete_skip(ann.id, habit.id, day(0), 3)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repos
+            .habits
+            .delete_skip(ann.id, habit.id, day(0), 3)
+            .await
+            .unwrap()
+    );
+    let ann_now = repos.users.find(ann.id).await.unwrap().unwrap();
+    assert_eq!(ann_now.streak_freezes, 1);
+
+    // Refunds stop at the cap
+    for period in [day(0), day(7)] {
+        repos
+            .habits
+            .award_streak_freeze(ann.id, habit.id, period, 3)
+            .await
+            .unwrap();
+    }
+    assert!(
+        repos
+            .habits
+            .delete_skip(ann.id, habit.id, day(-2), 3)
+            .await
+            .unwrap()
+    );
+    let ann_now = repos.users.find(ann.id).await.unwrap().unwrap();
+    assert_eq!(ann_now.streak_freezes, 3);
+}
+
+async fn habits_are_scoped_to_the_owner(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let habit = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+
+    // Bob can't spend his freezes on Ann's habit
+    assert!(
+        repos
+            .habits
+            .freeze(bob.id, habit.id, day(-1))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let bob_now = repos.users.find(bob.id).await.unwrap().unwrap();
+    assert_eq!(bob_now.streak_freezes, 2);
+    assert!(repos.habits.user_skips(ann.id).await.unwrap().is_empty());
+
+    // Nor check in on it, read its history or earn from it
+    assert!(
+        repos
+            .habits
+            .add_completion(bob.id, habit.id, day(0), None, true)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repos
+            .habits
+            .add_measurement(bob.id, habit.id, day(0), 1.0, true)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repos
+            .habits
+            .user_completions(ann.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        !repos
+            .habits
+            .award_streak_freeze(bob.id, habit.id, day(0), 3)
+            .await
+            .unwrap()
+    );
+    // Bob's attempt didn't use up Ann's payout for the period
+    repos
+        .habits
+        .freeze(ann.id, habit.id, day(-1))
+        .await
+        .unwrap();
+    assert!(
+        repos
+            .habits
+            .award_streak_freeze(ann.id, habit.id, day(0), 3)
+            .await
+            .unwrap()
+    );
+
+    repos
+        .habits
+        .add_completion(ann.id, habit.id, day(0), None, true)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        repos
+            .habits
+            .completions(bob.id, habit.id, None, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        repos
+            .habits
+            .skips(bob.id, habit.id, None, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        repos
+            .habits
+            .skips(ann.id, habit.id, None, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+async fn freeze_awards_once_per_period(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let habit = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+    let other = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Walk"})))
+        .await
+        .unwrap();
+    let award = |habit_id, period| {
+        repos
+            .habits
+            .award_streak_freeze(ann.id, habit_id, period, 3)
+    };
+
+    assert!(award(habit.id, day(0)).await.unwrap());
+    assert!(!award(habit.id, day(0)).await.unwrap());
+    // The next period pays out again, but never past the cap
+    assert!(!award(habit.id, day(7)).await.unwrap());
+    let ann_now = repos.users.find(ann.id).await.unwrap().unwrap();
+    assert_eq!(ann_now.streak_freezes, 3);
+
+    // A period reached at the cap is still spent
+    repos.habits.freeze(ann.id, habit.id, day(1)).await.unwrap();
+    assert!(!award(habit.id, day(7)).await.unwrap());
+    assert!(award(other.id, day(7)).await.unwrap());
+
+    // Awards go with their habit
+    assert!(repos.habits.delete(ann.id, habit.id).await.unwrap());
+    let again = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+    repos.habits.freeze(ann.id, again.id, day(1)).await.unwrap();
+    assert!(award(again.id, day(0)).await.unwrap());
+}
+
+async fn archive_and_pause(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let habit = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+
+    assert!(
+        repos
+            .habits
+            .set_archived(bob.id, habit.id, true)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let archived = repos
+        .habits
+        .set_archived(ann.id, habit.id, true)
+        .await
+        .unwrap()
+        .unwrap();
+    let archived_at = archived.archived_at.unwrap();
+    // Archiving again keeps the original time
+    let again = repos
+        .habits
+        .set_archived(ann.id, habit.id, true)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(again.archived_at, Some(archived_at));
+    assert!(repos.habits.list(ann.id, false).await.unwrap().is_empty());
+    assert_eq!(repos.habits.list(ann.id, true).await.unwrap().len(), 1);
+    assert!(
+        repos
+            .habits
+            .active(ann.id, day(0))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    repos
+        .habits
+        .set_archived(ann.id, habit.id, false)
+        .await
+        .unwrap();
+    repos
+        .habits
+        .pause(ann.id, habit.id, day(0), day(0))
+        .await
+        .unwrap();
+    // Paused through the given day
+    assert!(
+        repos
+            .habits
+            .active(ann.id, day(0))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(repos.habits.active(ann.id, day(1)).await.unwrap().len(), 1);
+    assert_eq!(repos.habits.list(ann.id, false).await.unwrap().len(), 1);
+}
+
+async fn pauses_are_recorded(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let habit = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+    let ranges = |pauses: Vec<HabitPause>| {
+        let mut ranges: Vec<_> = pauses.iter().map(|p| (p.starts_on, p.ends_on)).collect();
+        ranges.sort();
+        ranges
+    };
+
+    assert!(
+        repos
+            .habits
+            .pause(bob.id, habit.id, day(0), day(5))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    repos
+        .habits
+        .pause(ann.id, habit.id, day(0), day(5))
+        .await
+        .unwrap();
+    // Pausing again replaces the rest of the earlier pause
+    let paused = repos
+        .habits
+        .pause(ann.id, habit.id, day(2), day(3))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(paused.paused_until, Some(day(3)));
+    assert_eq!(
+        ranges(repos.habits.user_pauses(ann.id).await.unwrap()),
+        vec![(day(0), day(1)), (day(2), day(3))]
+    );
+
+    // Resuming ends the pause the day before
+    let resumed = repos
+        .habits
+        .resume(ann.id, habit.id, day(3))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(resumed.paused_until, None);
+    assert_eq!(
+        ranges(repos.habits.user_pauses(ann.id).await.unwrap()),
+        vec![(day(0), day(1)), (day(2), day(2))]
+    );
+    // A pause resumed on its first day is dropped
+    repos
+        .habits
+        .pause(ann.id, habit.id, day(3), day(4))
+        .await
+        .unwrap();
+    repos.habits.resume(ann.id, habit.id, day(3)).await.unwrap();
+    assert_eq!(repos.habits.user_pauses(ann.id).await.unwrap().len(), 2);
+    assert!(repos.habits.user_pauses(bob.id).await.unwrap().is_empty());
+}
+
+async fn delete_cascades(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let habit = repos
+        .habits
+        .create(ann.id, body(json!({"name": "Read"})))
+        .await
+        .unwrap();
+    repos
+        .habits
+        .add_completion(ann.id, habit.id, day(0), None, true)
+        .await
+        .unwrap();
+    repos
+        .habits
+        .skip(ann.id, habit.id, day(-1), None)
+        .await
+        .unwrap();
+
+    assert!(repos.habits.delete(ann.id, habit.id).await.unwrap());
+    assert!(repos.habits.find(ann.id, habit.id).await.unwrap().is_none());
+    assert!(
+        repos
+            .habits
+            .user_completions(ann.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        repos
+            .habits
+            .completions(ann.id, habit.id, None, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        repos
+            .habits
+            .skips(ann.id, habit.id, None, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+async fn vacations(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let later = repos
+        .habits
+        .create_vacation(
+            ann.id,
+            body(json!({"starts_on": day(30), "ends_on": day(40)})),
+        )
+        .await
+        .unwrap();
+    let sooner = repos
+        .habits
+        .create_vacation(
+            ann.id,
+            body(json!({"starts_on": day(5), "ends_on": day(7), "note": "Beach"})),
+        )
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = repos
+        .habits
+        .vacations(ann.id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|v| v.id)
+        .collect();
+    assert_eq!(ids, [sooner.id, later.id]);
+    assert!(repos.habits.vacations(bob.id).await.unwrap().is_empty());
+
+    assert!(
+        !repos
+            .habits
+            .delete_vacation(bob.id, later.id)
+            .await
+            .unwrap()
+    );
+    assert!(
+        repos
+            .habits
+            .delete_vacation(ann.id, later.id)
+            .await
+            .unwrap()
+    );
+    assert_eq!(repos.habits.vacations(ann.id).await.unwrap().len(), 1);
+}
@@ -0,0 +1,57 @@
+// The conformance suite for the repository layer: every case runs once against
+// the in-memory store and once against a fresh Postgres database, so the two
+// backends can't drift apart.
+use projekt::{
+    models::user::User,
+    repo::{Repos, users::NewUser},
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+// Expands to `memory::<case>` and `postgres::<case>` tests for each shared
+// `async fn <case>(repos: Repos)` in the invoking module
+macro_rules! conformance {
+    ($($case:ident),* $(,)?) => {
+        mod memory {
+            $(
+                #[tokio::test]
+                async fn $case() {
+                    super::$case(projekt::repo::Repos::in_memory()).await;
+                }
+            )*
+        }
+
+        mod postgres {
+            $(
+                #[sqlx::test(migrator = "projekt::db::MIGRATOR")]
+                async fn $case(pool: sqlx::PgPool) {
+                    super::$case(projekt::repo::Repos::postgres(pool)).await;
+                }
+            )*
+        }
+    };
+}
+
+mod goals;
+mod habits;
+mod pomodoro;
+mod tasks;
+mod users;
+
+pub async fn user(repos: &Repos, name: &str) -> User {
+    repos
+        .users
+        .create(NewUser {
+            username: name.to_string(),
+            email: format!("{name}@example.com"),
+            password_hash: "not a real hash".to_string(),
+            timezone: "UTC".to_string(),
+        })
+        .await
+        .unwrap()
+}
+
+// Request bodies don't implement Default; build them the way a client would
+pub fn body<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
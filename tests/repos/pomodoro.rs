@@ -0,0 +1,277 @@
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use projekt::{
+    models::pomodoro::{InterruptionKind, SessionStatus, SessionType},
+    repo::{
+        RepoError, Repos,
+        pomodoro::{NewSession, Started},
+    },
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{body, user};
+
+conformance!(
+    one_open_session,
+    replace_checks_task_first,
+    end_and_re_end,
+    pause_and_resume,
+    delete_cascades,
+    settings,
+);
+
+fn focus(task_id: Option<Uuid>, replace: bool) -> NewSession {
+    NewSession {
+        task_id,
+        session_type: Some(SessionType::Focus),
+        duration_minutes: None,
+        replace,
+    }
+}
+
+async fn start(repos: &Repos, user_id: Uuid, session: NewSession) -> Started {
+    repos
+        .pomodoro
+        .start(user_id, Tz::UTC, session)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+async fn one_open_session(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+
+    let first = start(&repos, ann.id, focus(None, false)).await;
+    assert_eq!(first.session.status, SessionStatus::Running);
+    assert_eq!(first.session.duration_minutes, 25);
+    assert!(first.replaced.is_none());
+
+    let err = repos
+        .pomodoro
+        .start(ann.id, Tz::UTC, focus(None, false))
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, RepoError::Duplicate));
+    // Other users have sessions of their own
+    start(&repos, bob.id, focus(None, false)).await;
+
+    let second = start(&repos, ann.id, focus(None, true)).await;
+    let replaced = second.replaced.unwrap();
+    assert_eq!(replaced.id, first.session.id);
+    assert_eq!(replaced.status, SessionStatus::Interrupted);
+    assert!(replaced.ended_at.is_some());
+
+    let active = repos.pomodoro.active(ann.id).await.unwrap().unwrap();
+    assert_eq!(active.id, second.session.id);
+    let ids: Vec<_> = repos
+        .pomodoro
+        .list(ann.id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(ids, [second.session.id, first.session.id]);
+}
+
+async fn replace_checks_task_first(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let bobs_task = repos
+        .tasks
+        .create(bob.id, body(json!({"title": "Bob's task"})))
+        .await
+        .unwrap();
+    let open = start(&repos, ann.id, focus(None, false)).await;
+
+    assert!(
+        repos
+            .pomodoro
+            .start(ann.id, Tz::UTC, focus(Some(bobs_task.id), true))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let active = repos.pomodoro.active(ann.id).await.unwrap().unwrap();
+    assert_eq!(active.id, open.session.id);
+    assert_eq!(active.status, SessionStatus::Running);
+}
+
+async fn end_and_re_end(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let started = start(&repos, ann.id, focus(None, false)).await;
+    let id = started.session.id;
+
+    assert!(
+        repos
+            .pomodoro
+            .end(bob.id, id, Tz::UTC, None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let ended = repos
+        .pomodoro
+        .end(ann.id, id, Tz::UTC, Some("Got distracted".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(ended.just_ended);
+    assert_eq!(ended.session.status, SessionStatus::Interrupted);
+    assert!(ended.next.is_none());
+
+    // Ending again only touches the notes
+    let again = repos
+        .pomodoro
+        .end(ann.id, id, Tz::UTC, Some("Phone call".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!again.just_ended);
+    assert_eq!(again.session.ended_at, ended.session.ended_at);
+    assert_eq!(again.session.notes.as_deref(), Some("Phone call"));
+    assert!(repos.pomodoro.active(ann.id).await.unwrap().is_none());
+
+    let today = Utc::now().date_naive();
+    let focus_sessions = repos
+        .pomodoro
+        .focus_sessions(
+            ann.id,
+            Tz::UTC,
+            today - Duration::days(1),
+            today + Duration::days(1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(focus_sessions.len(), 1);
+    assert_eq!(focus_sessions[0].session.id, id);
+}
+
+async fn pause_and_resume(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let id = start(&repos, ann.id, focus(None, false)).await.session.id;
+
+    assert!(repos.pomodoro.resume(ann.id, id).await.unwrap().is_none());
+    assert!(repos.pomodoro.pause(bob.id, id).await.unwrap().is_none());
+    let paused = repos.pomodoro.pause(ann.id, id).await.unwrap().unwrap();
+    assert_eq!(paused.status, SessionStatus::Paused);
+    assert!(paused.paused_at.is_some());
+    assert!(repos.pomodoro.pause(ann.id, id).await.unwrap().is_none());
+
+    let resumed = repos.pomodoro.resume(ann.id, id).await.unwrap().unwrap();
+    assert_eq!(resumed.status, SessionStatus::Running);
+    assert!(resumed.paused_at.is_none());
+    assert_eq!(resumed.paused_seconds, 0);
+
+    // Ending while paused folds the open pause in
+    repos.pomodoro.pause(ann.id, id).await.unwrap();
+    let ended = repos
+        .pomodoro
+        .end(ann.id, id, Tz::UTC, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ended.session.status, SessionStatus::Interrupted);
+    assert!(ended.session.paused_at.is_none());
+    assert!(repos.pomodoro.resume(ann.id, id).await.unwrap().is_none());
+}
+
+async fn delete_cascades(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let id = start(&repos, ann.id, focus(None, false)).await.session.id;
+
+    let now = Utc::now();
+    let later = repos
+        .pomodoro
+        .add_interruption(ann.id, id, InterruptionKind::External, now, None)
+        .await
+        .unwrap()
+        .unwrap();
+    let earlier = repos
+        .pomodoro
+        .add_interruption(
+            ann.id,
+            id,
+            InterruptionKind::Internal,
+            now - Duration::minutes(5),
+            Some("Email".to_string()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let ids: Vec<_> = repos
+        .pomodoro
+        .interruptions(ann.id, id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|i| i.id)
+        .collect();
+    assert_eq!(ids, [earlier.id, later.id]);
+
+    // Bob can neither log interruptions on Ann's session nor see hers
+    assert!(
+        repos
+            .pomodoro
+            .add_interruption(bob.id, id, InterruptionKind::External, now, None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repos
+            .pomodoro
+            .interruptions(bob.id, id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    assert!(!repos.pomodoro.delete(bob.id, id).await.unwrap());
+    assert!(repos.pomodoro.delete(ann.id, id).await.unwrap());
+    assert!(repos.pomodoro.find(ann.id, id).await.unwrap().is_none());
+    assert!(
+        repos
+            .pomodoro
+            .interruptions(ann.id, id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    // Deleting the open session frees the slot
+    start(&repos, ann.id, focus(None, false)).await;
+}
+
+async fn settings(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+
+    let defaults = repos.pomodoro.settings(ann.id).await.unwrap();
+    assert_eq!(defaults.focus_minutes, 25);
+    assert_eq!(defaults.short_break_minutes, 5);
+    assert_eq!(defaults.long_break_minutes, 15);
+    assert_eq!(defaults.long_break_every, 4);
+    assert!(!defaults.auto_start_next);
+
+    let updated = repos
+        .pomodoro
+        .update_settings(ann.id, body(json!({"focus_minutes": 50})))
+        .await
+        .unwrap();
+    assert_eq!(updated.focus_minutes, 50);
+    assert_eq!(updated.short_break_minutes, 5);
+    assert_eq!(
+        repos.pomodoro.settings(bob.id).await.unwrap().focus_minutes,
+        25
+    );
+
+    // Sessions take their length from the settings
+    let session = start(&repos, ann.id, focus(None, false)).await.session;
+    assert_eq!(session.duration_minutes, 50);
+}
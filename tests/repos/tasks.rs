@@ -0,0 +1,309 @@
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use projekt::{
+    models::pomodoro::SessionType,
+    repo::{Repos, pomodoro::NewSession},
+    timezone::today_in,
+};
+use serde_json::json;
+
+use crate::{body, user};
+
+conformance!(
+    scoped_to_owner,
+    completion,
+    delete_cascades_and_unlinks,
+    time_entries,
+    estimates_keep_their_pomodoro_length,
+);
+
+async fn scoped_to_owner(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let task = repos
+        .tasks
+        .create(ann.id, body(json!({"title": "Write report"})))
+        .await
+        .unwrap();
+
+    assert!(repos.tasks.list(bob.id).await.unwrap().is_empty());
+    assert!(repos.tasks.find(bob.id, task.id).await.unwrap().is_none());
+    assert!(
+        repos
+            .tasks
+            .update(bob.id, task.id, body(json!({"title": "Mine now"})))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repos
+            .tasks
+            .complete(bob.id, task.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(!repos.tasks.delete(bob.id, task.id).await.unwrap());
+    assert!(
+        repos
+            .tasks
+            .effort(bob.id, Some(task.id))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let found = repos.tasks.find(ann.id, task.id).await.unwrap().unwrap();
+    assert_eq!(found.title, "Write report");
+    assert_eq!(repos.tasks.list(ann.id).await.unwrap().len(), 1);
+}
+
+async fn completion(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let task = repos
+        .tasks
+        .create(
+            ann.id,
+            body(json!({"title": "Write report", "tags": ["work"]})),
+        )
+        .await
+        .unwrap();
+    assert!(!task.completed);
+    assert!(task.completed_at.is_none());
+    assert_eq!(task.tags, ["work"]);
+
+    let first = repos
+        .tasks
+        .complete(ann.id, task.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(first.newly_completed);
+    let completed_at = first.task.completed_at.unwrap();
+
+    // Completing again changes nothing
+    let again = repos
+        .tasks
+        .complete(ann.id, task.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!again.newly_completed);
+    assert_eq!(again.task.completed_at, Some(completed_at));
+    // Counted by the user's date, which is a day apart in these two zones
+    for tz in [Tz::Pacific__Kiritimati, Tz::Pacific__Pago_Pago] {
+        let today = today_in(tz);
+        let count = |after| repos.tasks.count_completed_after(ann.id, tz, after);
+        assert_eq!(count(today - Duration::days(1)).await.unwrap(), 1);
+        assert_eq!(count(today).await.unwrap(), 0);
+    }
+
+    // Reopening clears the completion time, other fields stay as they were
+    let reopened = repos
+        .tasks
+        .update(ann.id, task.id, body(json!({"completed": false})))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!reopened.newly_completed);
+    assert!(!reopened.task.completed);
+    assert!(reopened.task.completed_at.is_none());
+    assert_eq!(reopened.task.title, "Write report");
+    assert_eq!(reopened.task.tags, ["work"]);
+
+    let updated = repos
+        .tasks
+        .update(
+            ann.id,
+            task.id,
+            body(json!({"completed": true, "priority": "high"})),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(updated.newly_completed);
+    assert!(updated.task.completed_at.is_some());
+}
+
+async fn delete_cascades_and_unlinks(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let task = repos
+        .tasks
+        .create(ann.id, body(json!({"title": "Write report"})))
+        .await
+        .unwrap();
+    repos
+        .tasks
+        .add_time_entry(ann.id, task.id, 30, Utc::now().date_naive(), None)
+        .await
+        .unwrap();
+    let started = repos
+        .pomodoro
+        .start(
+            ann.id,
+            Tz::UTC,
+            NewSession {
+                task_id: Some(task.id),
+                session_type: Some(SessionType::Focus),
+                duration_minutes: None,
+                replace: false,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(started.session.task_id, Some(task.id));
+
+    assert!(repos.tasks.delete(ann.id, task.id).await.unwrap());
+    assert!(!repos.tasks.delete(ann.id, task.id).await.unwrap());
+
+    // The session outlives its task, unlinked
+    assert!(
+        repos
+            .tasks
+            .time_entries(ann.id, task.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let session = repos
+        .pomodoro
+        .find(ann.id, started.session.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.task_id, None);
+}
+
+async fn time_entries(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let bob = user(&repos, "bob").await;
+    let task = repos
+        .tasks
+        .create(ann.id, body(json!({"title": "Write report"})))
+        .await
+        .unwrap();
+    let today = Utc::now().date_naive();
+
+    let later = repos
+        .tasks
+        .add_time_entry(ann.id, task.id, 45, today, Some("Editing".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    let earlier = repos
+        .tasks
+        .add_time_entry(ann.id, task.id, 30, today - Duration::days(1), None)
+        .await
+        .unwrap()
+        .unwrap();
+    let entries = repos.tasks.time_entries(ann.id, task.id).await.unwrap();
+    let ids: Vec<_> = entries.iter().map(|e| e.id).collect();
+    assert_eq!(ids, [earlier.id, later.id]);
+
+    // Bob can neither log time on Ann's task nor see hers
+    assert!(
+        repos
+            .tasks
+            .add_time_entry(bob.id, task.id, 10, today, None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repos
+            .tasks
+            .time_entries(bob.id, task.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let effort = repos.tasks.effort(ann.id, Some(task.id)).await.unwrap();
+    assert_eq!(effort.len(), 1);
+    assert_eq!(effort[0].manual_minutes, 75);
+    assert_eq!(effort[0].focus_seconds, 0);
+    assert_eq!(effort[0].minutes_per_pomodoro, 25);
+
+    assert!(
+        !repos
+            .tasks
+            .delete_time_entry(bob.id, task.id, earlier.id)
+            .await
+            .unwrap()
+    );
+    assert!(
+        repos
+            .tasks
+            .delete_time_entry(ann.id, task.id, earlier.id)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        repos
+            .tasks
+            .time_entries(ann.id, task.id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+async fn estimates_keep_their_pomodoro_length(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    let minutes_per_pomodoro = async |task_id| {
+        repos.tasks.effort(ann.id, Some(task_id)).await.unwrap()[0].minutes_per_pomodoro
+    };
+    let focus_minutes = async |minutes: i32| {
+        repos
+            .pomodoro
+            .update_settings(ann.id, body(json!({"focus_minutes": minutes})))
+            .await
+            .unwrap();
+    };
+
+    let old = repos
+        .tasks
+        .create(
+            ann.id,
+            body(json!({"title": "Old", "estimated_pomodoros": 2})),
+        )
+        .await
+        .unwrap();
+    assert_eq!(old.pomodoro_minutes, Some(25));
+    let unestimated = repos
+        .tasks
+        .create(ann.id, body(json!({"title": "Unestimated"})))
+        .await
+        .unwrap();
+    assert_eq!(unestimated.pomodoro_minutes, None);
+
+    // Longer pomodoros from now on don't change what "2 pomodoros" meant before
+    focus_minutes(50).await;
+    assert_eq!(minutes_per_pomodoro(old.id).await, 25);
+    let new = repos
+        .tasks
+        .create(
+            ann.id,
+            body(json!({"title": "New", "estimated_pomodoros": 2})),
+        )
+        .await
+        .unwrap();
+    assert_eq!(minutes_per_pomodoro(new.id).await, 50);
+
+    // Re-estimating takes the current length; other edits keep the old one
+    repos
+        .tasks
+        .update(ann.id, old.id, body(json!({"title": "Old, renamed"})))
+        .await
+        .unwrap();
+    assert_eq!(minutes_per_pomodoro(old.id).await, 25);
+    repos
+        .tasks
+        .update(ann.id, old.id, body(json!({"estimated_pomodoros": 3})))
+        .await
+        .unwrap();
+    assert_eq!(minutes_per_pomodoro(old.id).await, 50);
+}
@@ -0,0 +1,76 @@
+use projekt::repo::{RepoError, Repos, users::NewUser};
+
+use crate::user;
+
+conformance!(unique_email_and_username, timezone_update);
+
+async fn unique_email_and_username(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+    assert_eq!(ann.streak_freezes, 2);
+
+    let taken = |username: &str, email: &str| NewUser {
+        username: username.to_string(),
+        email: email.to_string(),
+        password_hash: "not a real hash".to_string(),
+        timezone: "UTC".to_string(),
+    };
+    let err = repos
+        .users
+        .create(taken("other", "ann@example.com"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RepoError::Duplicate));
+    let err = repos
+        .users
+        .create(taken("ann", "other@example.com"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RepoError::Duplicate));
+
+    let found = repos.users.find_by_email("ann@example.com").await.unwrap();
+    assert_eq!(found.unwrap().id, ann.id);
+    assert!(
+        repos
+            .users
+            .find_by_email("nobody@example.com")
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+async fn timezone_update(repos: Repos) {
+    let ann = user(&repos, "ann").await;
+
+    let updated = repos
+        .users
+        .update_timezone(ann.id, Some("Europe/Berlin".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.timezone, "Europe/Berlin");
+
+    // None keeps what's there
+    let kept = repos
+        .users
+        .update_timezone(ann.id, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(kept.timezone, "Europe/Berlin");
+    assert_eq!(
+        repos.users.timezone(ann.id).await.unwrap().as_deref(),
+        Some("Europe/Berlin")
+    );
+
+    let nobody = uuid::Uuid::new_v4();
+    assert!(repos.users.timezone(nobody).await.unwrap().is_none());
+    assert!(
+        repos
+            .users
+            .update_timezone(nobody, None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}